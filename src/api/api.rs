@@ -4,14 +4,112 @@
 // - Provides an interface for external applications to interact with the quantum network.
 // - Exposes functionalities for node creation, entanglement, key exchange, and secure messaging.
 
-use crate::core::quantum_node::QuantumNode;
+use crate::core::quantum_cryptography::{Bb84Result, QkdError, QuantumCryptography, MIN_KEY_WEIGHT_RATIO};
+use crate::core::quantum_network::QuantumState;
+use crate::core::quantum_node::{KeyUsage, QuantumNode, SendFailure};
+use crate::core::qkd_limiter::{QkdLimiter, DEFAULT_MAX_CONCURRENT_QKD, DEFAULT_QKD_PERMIT_TIMEOUT};
 use crate::core::quantum_packet::QuantumPacket;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use rand::Rng;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Length in bytes of keys generated for trusted-node relaying.
+const RELAY_KEY_LENGTH: usize = 16;
+
+/// Number of SHA-256 bytes kept in a key fingerprint.
+const KEY_FINGERPRINT_BYTES: usize = 8;
+
+/// Number of pending status notifications buffered per subscriber before it lags.
+const STATUS_CHANNEL_CAPACITY: usize = 64;
+
+/// Number of qubits transmitted by a verbose (BB84) key exchange.
+const BB84_QUBITS: usize = 1024;
+
+/// QBER above which a verbose key exchange is aborted.
+const BB84_QBER_THRESHOLD: f64 = 0.11;
+
+/// Default probability that the quantum channel flips a transmitted BB84 bit.
+pub const DEFAULT_CHANNEL_ERROR_RATE: f64 = 0.02;
+
+/// Outcome of relaying a key through a chain of trusted nodes.
+///
+/// Trusted-node relaying is weaker than end-to-end QKD: every intermediate node
+/// decrypts and re-encrypts the key, so each of them learns it.
+#[derive(Debug, Clone)]
+pub struct KeyRelay {
+    pub key: Vec<u8>,            // Key now shared by both endpoints
+    pub trusted_nodes: Vec<u32>, // Intermediate nodes that saw the key in plaintext
+}
+
+/// Identifies the key a node holds for one peer without revealing it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeerKeyInfo {
+    pub peer_id: u32,            // The peer the key is shared with
+    pub key_fingerprint: String, // Hex of a truncated SHA-256 of the key
+    pub key_len: usize,          // Key length in bytes
+}
+
+/// Snapshot of a node's entanglement and key material.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStatus {
+    pub entangled_nodes: Vec<u32>,   // IDs of entangled peers
+    pub key_count: usize,            // Number of stored keys
+    pub peer_keys: Vec<PeerKeyInfo>, // Fingerprint of each stored key, by ascending peer ID
+}
+
+/// Result of comparing the key material two nodes hold for each other.
+///
+/// Reports only presence and equality; the keys themselves are never exposed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyComparison {
+    pub first_has_key: bool,  // The first node stores a key for the second
+    pub second_has_key: bool, // The second node stores a key for the first
+    pub keys_match: bool,     // Both keys exist and are identical byte-for-byte
+}
+
+/// Errors returned by `QuantumAPI` operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuantumApiError {
+    NodeAlreadyExists(u32),       // A node with this ID is already registered
+    NodeNotFound(u32),            // No node with this ID is registered
+    SelfPair(u32),                // The operation requires two distinct nodes
+    EntanglementFailed(u32, u32), // The nodes exist but entanglement could not be established
+    NotEntangled(u32, u32),       // The nodes must be entangled first
+    EavesdropperDetected,         // The estimated QBER exceeded the detection threshold
+    InsufficientKeyMaterial,      // Too few bits survived sifting to form a key
+    KeyExchangeFailed,            // QKD failed for an internal reason
+    QkdBusy,                      // No QKD permit became free before the timeout
+    SendFailed(SendFailure),      // The message could not be sent
+}
+
+impl fmt::Display for QuantumApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QuantumApiError::NodeAlreadyExists(id) => write!(f, "Node {} already exists.", id),
+            QuantumApiError::NodeNotFound(id) => write!(f, "Node {} not found.", id),
+            QuantumApiError::SelfPair(id) => write!(f, "Node {} cannot be paired with itself.", id),
+            QuantumApiError::EntanglementFailed(a, b) => write!(f, "Failed to entangle nodes {} and {}.", a, b),
+            QuantumApiError::NotEntangled(a, b) => write!(f, "Nodes {} and {} are not entangled.", a, b),
+            QuantumApiError::EavesdropperDetected => write!(f, "Possible eavesdropper detected: QBER too high."),
+            QuantumApiError::InsufficientKeyMaterial => write!(f, "Too few bits survived sifting to form a key."),
+            QuantumApiError::KeyExchangeFailed => write!(f, "Quantum key exchange failed."),
+            QuantumApiError::QkdBusy => write!(f, "Too many concurrent key exchanges; try again later."),
+            QuantumApiError::SendFailed(reason) => write!(f, "Failed to send message: {}", reason),
+        }
+    }
+}
 
 /// Represents the global quantum network API.
 pub struct QuantumAPI {
-    nodes: Arc<Mutex<HashMap<u32, QuantumNode>>>, // Stores all registered quantum nodes
+    nodes: Arc<RwLock<HashMap<u32, QuantumNode>>>, // Stores all registered quantum nodes
+    qkd_limiter: QkdLimiter, // Bounds concurrent key exchanges
+    status_updates: broadcast::Sender<u32>, // Announces IDs of nodes whose links or keys changed
+    channel_error_rate: f64, // Probability that a link flips a transmitted BB84 bit
 }
 
 impl QuantumAPI {
@@ -20,60 +118,245 @@ impl QuantumAPI {
     /// # Returns
     /// * `QuantumAPI` - A new instance managing the quantum network.
     pub fn new() -> Self {
+        QuantumAPI::with_qkd_limit(DEFAULT_MAX_CONCURRENT_QKD, DEFAULT_QKD_PERMIT_TIMEOUT)
+    }
+
+    /// Creates a new instance of the quantum API with a custom QKD concurrency limit.
+    ///
+    /// # Arguments
+    /// * `max_concurrent` - Maximum number of key exchanges running at once.
+    /// * `timeout` - How long an excess key exchange waits for a slot.
+    ///
+    /// # Returns
+    /// * `QuantumAPI` - A new instance managing the quantum network.
+    pub fn with_qkd_limit(max_concurrent: usize, timeout: Duration) -> Self {
         QuantumAPI {
-            nodes: Arc::new(Mutex::new(HashMap::new())),
+            nodes: Arc::new(RwLock::new(HashMap::new())),
+            qkd_limiter: QkdLimiter::new(max_concurrent, timeout),
+            status_updates: broadcast::channel(STATUS_CHANNEL_CAPACITY).0,
+            channel_error_rate: DEFAULT_CHANNEL_ERROR_RATE,
         }
     }
 
+    /// Sets the bit error rate of the links used by verbose (BB84) key exchanges.
+    ///
+    /// The rate feeds the simulated channel noise, so it shows up in the reported QBER;
+    /// rates above the QBER threshold make exchanges fail as if eavesdropped.
+    ///
+    /// # Arguments
+    /// * `error_rate` - Probability of a bit flip per transmitted qubit, clamped to [0, 1].
+    pub fn set_channel_error_rate(&mut self, error_rate: f64) {
+        self.channel_error_rate = error_rate.clamp(0.0, 1.0);
+    }
+
+    /// Subscribes to node status changes.
+    ///
+    /// The receiver yields the ID of every node whose entanglement or key store changed.
+    ///
+    /// # Returns
+    /// * `broadcast::Receiver<u32>` - A stream of changed node IDs.
+    pub fn subscribe_status(&self) -> broadcast::Receiver<u32> {
+        self.status_updates.subscribe()
+    }
+
+    /// Returns the number of key exchanges currently running.
+    pub fn active_key_exchanges(&self) -> usize {
+        self.qkd_limiter.in_use()
+    }
+
     /// Registers a new quantum node in the network.
     ///
     /// # Arguments
     /// * `node_id` - Unique identifier for the quantum node.
     ///
     /// # Returns
-    /// * `true` if the node was successfully added, `false` if it already exists.
-    pub fn register_node(&self, node_id: u32) -> bool {
-        let mut nodes = self.nodes.lock().unwrap();
+    /// * `Ok(())` if the node was successfully added.
+    /// * `Err(QuantumApiError::NodeAlreadyExists)` if the ID is taken.
+    pub fn register_node(&self, node_id: u32) -> Result<(), QuantumApiError> {
+        let mut nodes = self.write_nodes();
         if nodes.contains_key(&node_id) {
-            false // Node already exists
+            Err(QuantumApiError::NodeAlreadyExists(node_id))
         } else {
             nodes.insert(node_id, QuantumNode::new(node_id));
-            true
+            self.notify_status(&[node_id]);
+            Ok(())
         }
     }
 
+    /// Lists the IDs of all registered nodes.
+    ///
+    /// # Returns
+    /// * `Vec<u32>` - The node IDs in ascending order.
+    pub fn list_nodes(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.read_nodes().keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
     /// Establishes quantum entanglement between two nodes.
     ///
+    /// Both nodes are updated under a single hold of the node-map write lock, so concurrent
+    /// calls for the same pair are serialized. Repeating the call is idempotent and
+    /// never records a duplicate link.
+    ///
     /// # Arguments
     /// * `node1` - The first node's ID.
     /// * `node2` - The second node's ID.
     ///
     /// # Returns
-    /// * `true` if entanglement was successful, `false` otherwise.
-    pub fn entangle_nodes(&self, node1: u32, node2: u32) -> bool {
-        let mut nodes = self.nodes.lock().unwrap();
-        if let (Some(node_a), Some(node_b)) = (nodes.get_mut(&node1), nodes.get_mut(&node2)) {
-            node_a.entangle_with(node2) && node_b.entangle_with(node1)
+    /// * `Ok(())` if entanglement was successful.
+    /// * `Err(QuantumApiError)` describing why the nodes could not be entangled.
+    pub fn entangle_nodes(&self, node1: u32, node2: u32) -> Result<(), QuantumApiError> {
+        if node1 == node2 {
+            return Err(QuantumApiError::SelfPair(node1));
+        }
+
+        let mut nodes = self.write_nodes();
+        Self::require_nodes(&nodes, node1, node2)?;
+
+        let entangled = nodes.get_mut(&node1).map_or(false, |node| node.entangle_with(node2))
+            && nodes.get_mut(&node2).map_or(false, |node| node.entangle_with(node1));
+        if entangled {
+            self.notify_status(&[node1, node2]);
+            Ok(())
         } else {
-            false
+            Err(QuantumApiError::EntanglementFailed(node1, node2))
         }
     }
 
+    /// Breaks the entanglement between two nodes, modelling a link going down.
+    ///
+    /// The link and any key shared over it are removed from both nodes, so neither
+    /// side is left holding a stale key. Both nodes' halves of the pair decohere to the
+    /// ground state, and every node's cached next hops are dropped, since routes may
+    /// have run over the broken link.
+    ///
+    /// # Arguments
+    /// * `node1` - The first node's ID.
+    /// * `node2` - The second node's ID.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the entanglement was broken.
+    /// * `Err(QuantumApiError)` if the nodes are the same, missing, or not entangled.
+    pub fn break_entanglement(&self, node1: u32, node2: u32) -> Result<(), QuantumApiError> {
+        if node1 == node2 {
+            return Err(QuantumApiError::SelfPair(node1));
+        }
+
+        let mut nodes = self.write_nodes();
+        Self::require_entangled(&nodes, node1, node2)?;
+
+        for (id, peer) in [(node1, node2), (node2, node1)] {
+            if let Some(node) = nodes.get_mut(&id) {
+                node.entangled_nodes.retain(|&linked| linked != peer);
+                node.key_store.remove(&peer);
+                node.key_usage.remove(&peer);
+                if matches!(node.state, QuantumState::Entangled(_) | QuantumState::Bell(_)) {
+                    node.state = QuantumState::Zero;
+                }
+            }
+        }
+        for node in nodes.values_mut() {
+            node.next_hop_cache.clear();
+        }
+        self.notify_status(&[node1, node2]);
+        Ok(())
+    }
+
     /// Initiates Quantum Key Distribution (QKD) between two entangled nodes.
     ///
+    /// The key is generated without holding the node lock, so exchanges for different
+    /// pairs run concurrently, up to the QKD concurrency limit.
+    ///
     /// # Arguments
     /// * `node1` - The first node's ID.
     /// * `node2` - The second node's ID.
     ///
     /// # Returns
-    /// * `true` if key exchange was successful, `false` otherwise.
-    pub fn exchange_keys(&self, node1: u32, node2: u32) -> bool {
-        let mut nodes = self.nodes.lock().unwrap();
-        if let (Some(node_a), Some(node_b)) = (nodes.get_mut(&node1), nodes.get_mut(&node2)) {
-            node_a.exchange_keys(node2) && node_b.exchange_keys(node1)
-        } else {
-            false
+    /// * `Ok(())` if key exchange was successful.
+    /// * `Err(QuantumApiError::SelfPair)` if `node1` and `node2` are the same node.
+    /// * `Err(QuantumApiError::QkdBusy)` if the concurrency limit stayed reached until the timeout.
+    /// * `Err(QuantumApiError::KeyExchangeFailed)` if the generated key fails the Hamming-weight check.
+    /// * `Err(QuantumApiError)` describing why the key exchange failed otherwise.
+    pub async fn exchange_keys(&self, node1: u32, node2: u32) -> Result<(), QuantumApiError> {
+        if node1 == node2 {
+            return Err(QuantumApiError::SelfPair(node1));
+        }
+
+        let _permit = self.qkd_limiter.acquire().await.ok_or(QuantumApiError::QkdBusy)?;
+        Self::require_entangled(&self.read_nodes(), node1, node2)?;
+
+        // One key for the pair, stored by both nodes
+        let mut rng = rand::thread_rng();
+        let key = QuantumCryptography::generate_shared_key(rng.gen(), rng.gen());
+        self.store_shared_key(node1, node2, &key)
+    }
+
+    /// Runs a BB84 key exchange between two entangled nodes and reports the sift outcome.
+    ///
+    /// On success both nodes store the sifted key for each other, replacing any
+    /// previous key and resetting its usage. The channel flips bits at the configured
+    /// channel error rate (see `set_channel_error_rate`). The simulation runs on a blocking thread
+    /// without holding the node lock, so exchanges for different pairs run concurrently,
+    /// up to the QKD concurrency limit.
+    ///
+    /// # Arguments
+    /// * `node1` - The sending node's ID.
+    /// * `node2` - The receiving node's ID.
+    ///
+    /// # Returns
+    /// * `Ok(Bb84Result)` with the sifted key length and estimated QBER.
+    /// * `Err(QuantumApiError::EavesdropperDetected)` if the QBER check fails.
+    /// * `Err(QuantumApiError::InsufficientKeyMaterial)` if too few bits remain after sifting.
+    /// * `Err(QuantumApiError::KeyExchangeFailed)` if the simulation thread failed.
+    /// * `Err(QuantumApiError::QkdBusy)` if the concurrency limit stayed reached until the timeout.
+    /// * `Err(QuantumApiError)` describing why the nodes cannot exchange keys otherwise.
+    pub async fn exchange_keys_verbose(&self, node1: u32, node2: u32) -> Result<Bb84Result, QuantumApiError> {
+        if node1 == node2 {
+            return Err(QuantumApiError::SelfPair(node1));
+        }
+
+        let _permit = self.qkd_limiter.acquire().await.ok_or(QuantumApiError::QkdBusy)?;
+        Self::require_entangled(&self.read_nodes(), node1, node2)?;
+
+        let error_rate = self.channel_error_rate;
+        let result = tokio::task::spawn_blocking(move || {
+            QuantumCryptography::bb84_sift(&mut rand::thread_rng(), BB84_QUBITS, error_rate, BB84_QBER_THRESHOLD)
+        })
+        .await
+        .map_err(|_| QuantumApiError::KeyExchangeFailed)? // The blocking task panicked or was cancelled
+        .map_err(|error| match error {
+            QkdError::EavesdropperDetected => QuantumApiError::EavesdropperDetected,
+            QkdError::InsufficientKeyMaterial => QuantumApiError::InsufficientKeyMaterial,
+            _ => QuantumApiError::KeyExchangeFailed,
+        })?;
+
+        let mut nodes = self.write_nodes();
+        Self::require_entangled(&nodes, node1, node2)?; // The link may have broken meanwhile
+        for (id, peer, key) in [(node1, node2, &result.key), (node2, node1, &result.peer_key)] {
+            if let Some(node) = nodes.get_mut(&id) {
+                node.key_store.insert(peer, key.clone());
+                node.key_usage.insert(peer, KeyUsage::default());
+            }
         }
+        self.notify_status(&[node1, node2]);
+
+        Ok(result)
+    }
+
+    /// Runs QKD between two entangled nodes and returns the generated key.
+    ///
+    /// Intended for external key-management systems; the key is also stored by both nodes.
+    ///
+    /// # Arguments
+    /// * `node1` - The first node's ID.
+    /// * `node2` - The second node's ID.
+    ///
+    /// # Returns
+    /// * `Ok((Vec<u8>, f64))` - The key and the estimated QBER.
+    /// * `Err(QuantumApiError)` describing why the key exchange failed.
+    pub async fn perform_qkd(&self, node1: u32, node2: u32) -> Result<(Vec<u8>, f64), QuantumApiError> {
+        self.exchange_keys_verbose(node1, node2).await.map(|result| (result.key, result.qber))
     }
 
     /// Sends a quantum-secure message between two nodes.
@@ -84,18 +367,53 @@ impl QuantumAPI {
     /// * `message` - The plaintext message to send.
     ///
     /// # Returns
-    /// * `Option<QuantumPacket>` - The encrypted packet if successful.
-    pub fn send_message(&self, sender_id: u32, receiver_id: u32, message: &str) -> Option<QuantumPacket> {
-        let nodes = self.nodes.lock().unwrap();
-        if let Some(sender) = nodes.get(&sender_id) {
-            sender.send_packet(receiver_id, message)
-        } else {
-            None
+    /// * `Ok(QuantumPacket)` - The encrypted packet.
+    /// * `Err(QuantumApiError::NodeNotFound)` if the sender is not registered.
+    /// * `Err(QuantumApiError::SendFailed)` with the exact reason the message could not be sent.
+    pub fn send_message(&self, sender_id: u32, receiver_id: u32, message: &str) -> Result<QuantumPacket, QuantumApiError> {
+        let mut nodes = self.write_nodes();
+        if !nodes.contains_key(&receiver_id) {
+            return Err(QuantumApiError::SendFailed(SendFailure::UnknownReceiver(receiver_id)));
         }
+
+        let sender = nodes.get_mut(&sender_id).ok_or(QuantumApiError::NodeNotFound(sender_id))?;
+        sender.send_packet(receiver_id, message).map_err(QuantumApiError::SendFailed)
+    }
+
+    /// Sends a batch of messages from one sender, taking the lock once for the whole batch.
+    ///
+    /// # Arguments
+    /// * `sender_id` - The ID of the sender node.
+    /// * `batch` - The receiver ID and plaintext of each message.
+    ///
+    /// # Returns
+    /// * `Vec<Result<QuantumPacket, QuantumApiError>>` - One result per entry, in batch order,
+    ///   with the same meaning as the result of `send_message`.
+    pub fn send_messages(&self, sender_id: u32, batch: &[(u32, String)]) -> Vec<Result<QuantumPacket, QuantumApiError>> {
+        let mut nodes = self.write_nodes();
+        let known_receivers: Vec<bool> = batch.iter().map(|(receiver_id, _)| nodes.contains_key(receiver_id)).collect();
+
+        let sender = match nodes.get_mut(&sender_id) {
+            Some(sender) => sender,
+            None => return batch.iter().map(|_| Err(QuantumApiError::NodeNotFound(sender_id))).collect(),
+        };
+
+        batch
+            .iter()
+            .zip(known_receivers)
+            .map(|((receiver_id, message), known)| {
+                if !known {
+                    return Err(QuantumApiError::SendFailed(SendFailure::UnknownReceiver(*receiver_id)));
+                }
+                sender.send_packet(*receiver_id, message).map_err(QuantumApiError::SendFailed)
+            })
+            .collect()
     }
 
     /// Receives and decrypts a quantum-secure message.
     ///
+    /// Takes the write lock, since decryption consumes the receiver's key material.
+    ///
     /// # Arguments
     /// * `receiver_id` - The ID of the receiver node.
     /// * `packet` - The incoming encrypted quantum packet.
@@ -103,23 +421,429 @@ impl QuantumAPI {
     /// # Returns
     /// * `Option<String>` - The decrypted message if successful.
     pub fn receive_message(&self, receiver_id: u32, packet: QuantumPacket) -> Option<String> {
-        let nodes = self.nodes.lock().unwrap();
-        if let Some(receiver) = nodes.get(&receiver_id) {
+        let mut nodes = self.write_nodes();
+        if let Some(receiver) = nodes.get_mut(&receiver_id) {
             receiver.receive_packet(&packet)
         } else {
             None
         }
     }
 
+    /// Relays a fresh key between the endpoints of `path` using trusted-node hopping.
+    ///
+    /// Use this only when end-to-end entanglement is infeasible. Each hop must already
+    /// share a QKD key; the relayed key is encrypted per hop and decrypted by the next
+    /// node, so every intermediate node sees it in plaintext. Each hop consumes unused
+    /// one-time-pad bytes on both sides, exactly as a message would, so the pad is never
+    /// reused by later messages. The endpoints' usage of the relayed key starts fresh.
+    ///
+    /// # Arguments
+    /// * `path` - Node IDs from source to destination (at least two).
+    ///
+    /// # Returns
+    /// * `Option<KeyRelay>` - The shared key and the trusted nodes that handled it,
+    ///   or `None` if the path ends where it starts or visits a node twice, a node or
+    ///   hop key is missing, a hop has too little unused key material left, or the hop
+    ///   keys disagree. Nothing is consumed on failure.
+    pub fn relay_key(&self, path: &[u32]) -> Option<KeyRelay> {
+        if path.len() < 2 {
+            return None;
+        }
+        let mut visited = HashSet::new();
+        if !path.iter().all(|id| visited.insert(*id)) {
+            return None; // Covers source == destination as well as loops through a relay
+        }
+
+        let mut nodes = self.write_nodes();
+        let mut rng = rand::thread_rng();
+        let key: Vec<u8> = (0..RELAY_KEY_LENGTH).map(|_| rng.gen()).collect();
+
+        // Hop the key across each link: encrypt at the sender, decrypt at the receiver,
+        // each with the next unused pad bytes of its hop key.
+        let mut in_transit = key.clone();
+        let mut consumed = Vec::new(); // (node, peer, new usage offset), applied once every hop succeeds
+        for hop in path.windows(2) {
+            let (from, to) = (hop[0], hop[1]);
+            let (sender_pad, sender_end) = Self::unused_pad(&nodes, from, to, RELAY_KEY_LENGTH)?;
+            let (receiver_pad, receiver_end) = Self::unused_pad(&nodes, to, from, RELAY_KEY_LENGTH)?;
+            let ciphertext = QuantumCryptography::encrypt_bytes(&in_transit, sender_pad);
+            in_transit = QuantumCryptography::encrypt_bytes(&ciphertext, receiver_pad);
+            consumed.push((from, to, sender_end));
+            consumed.push((to, from, receiver_end));
+        }
+
+        if in_transit != key {
+            return None; // A hop's keys were not identical on both sides
+        }
+
+        for (id, peer, end) in consumed {
+            if let Some(node) = nodes.get_mut(&id) {
+                node.key_usage.entry(peer).or_default().used = end;
+            }
+        }
+
+        let (source, destination) = (path[0], path[path.len() - 1]);
+        for (id, peer) in [(source, destination), (destination, source)] {
+            let node = nodes.get_mut(&id)?;
+            node.key_store.insert(peer, key.clone());
+            node.key_usage.insert(peer, KeyUsage::default());
+        }
+        self.notify_status(&[source, destination]);
+
+        Some(KeyRelay {
+            key,
+            trusted_nodes: path[1..path.len() - 1].to_vec(),
+        })
+    }
+
+    /// Compares the keys two nodes hold for each other, for diagnosing decryption failures.
+    ///
+    /// # Arguments
+    /// * `node1` - The first node's ID.
+    /// * `node2` - The second node's ID.
+    ///
+    /// # Returns
+    /// * `Ok(KeyComparison)` describing key presence and equality.
+    /// * `Err(QuantumApiError::NodeNotFound)` if either node is not registered.
+    pub fn compare_keys(&self, node1: u32, node2: u32) -> Result<KeyComparison, QuantumApiError> {
+        let nodes = self.read_nodes();
+        Self::require_nodes(&nodes, node1, node2)?;
+
+        let first_key = nodes[&node1].key_store.get(&node2);
+        let second_key = nodes[&node2].key_store.get(&node1);
+        Ok(KeyComparison {
+            first_has_key: first_key.is_some(),
+            second_has_key: second_key.is_some(),
+            keys_match: first_key.is_some() && first_key == second_key,
+        })
+    }
+
     /// Retrieves the status of a quantum node.
     ///
     /// # Arguments
     /// * `node_id` - The ID of the node.
     ///
     /// # Returns
-    /// * `Option<(Vec<u32>, usize)>` - A tuple containing entangled nodes and key count.
-    pub fn get_node_status(&self, node_id: u32) -> Option<(Vec<u32>, usize)> {
-        let nodes = self.nodes.lock().unwrap();
-        nodes.get(&node_id).map(|node| (node.entangled_nodes.clone(), node.key_store.len()))
+    /// * `Option<NodeStatus>` - The node's entangled peers and key fingerprints, if it exists.
+    pub fn get_node_status(&self, node_id: u32) -> Option<NodeStatus> {
+        let nodes = self.read_nodes();
+        let node = nodes.get(&node_id)?;
+
+        let mut peer_keys: Vec<PeerKeyInfo> = node
+            .key_store
+            .iter()
+            .map(|(&peer_id, key)| PeerKeyInfo {
+                peer_id,
+                key_fingerprint: Self::key_fingerprint(key),
+                key_len: key.len(),
+            })
+            .collect();
+        peer_keys.sort_by_key(|info| info.peer_id);
+
+        Some(NodeStatus {
+            entangled_nodes: node.entangled_nodes.clone(),
+            key_count: node.key_store.len(),
+            peer_keys,
+        })
+    }
+
+    /// Stores a freshly generated key in both nodes of an entangled pair, rejecting keys
+    /// that fail the Hamming-weight check with `KeyExchangeFailed`.
+    fn store_shared_key(&self, node1: u32, node2: u32, key: &[u8]) -> Result<(), QuantumApiError> {
+        QuantumCryptography::check_key_weight(key, MIN_KEY_WEIGHT_RATIO)
+            .map_err(|_| QuantumApiError::KeyExchangeFailed)?;
+
+        let mut nodes = self.write_nodes();
+        Self::require_entangled(&nodes, node1, node2)?; // The link may have broken meanwhile
+        let exchanged = nodes.get_mut(&node1).map_or(false, |node| node.exchange_keys(node2, key))
+            && nodes.get_mut(&node2).map_or(false, |node| node.exchange_keys(node1, key));
+        if exchanged {
+            self.notify_status(&[node1, node2]);
+            Ok(())
+        } else {
+            Err(QuantumApiError::KeyExchangeFailed)
+        }
+    }
+
+    /// Computes a key fingerprint: the hex of the first bytes of its SHA-256 digest.
+    fn key_fingerprint(key: &[u8]) -> String {
+        Sha256::digest(key)[..KEY_FINGERPRINT_BYTES]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Announces that the given nodes' entanglement or keys changed.
+    fn notify_status(&self, node_ids: &[u32]) {
+        for &id in node_ids {
+            let _ = self.status_updates.send(id); // No subscribers is not an error
+        }
+    }
+
+    /// Acquires shared read access to the node map.
+    ///
+    /// A panic in one request should not fail every later request, so a poisoned
+    /// lock is recovered rather than propagated.
+    fn read_nodes(&self) -> RwLockReadGuard<'_, HashMap<u32, QuantumNode>> {
+        self.nodes.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Acquires exclusive write access to the node map, recovering from poisoning.
+    fn write_nodes(&self) -> RwLockWriteGuard<'_, HashMap<u32, QuantumNode>> {
+        self.nodes.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the next `len` unused pad bytes of the key `id` holds for `peer`, and the
+    /// usage offset after consuming them, or `None` if the key is missing or too short.
+    fn unused_pad(nodes: &HashMap<u32, QuantumNode>, id: u32, peer: u32, len: usize) -> Option<(&[u8], usize)> {
+        let node = nodes.get(&id)?;
+        let key = node.key_store.get(&peer)?;
+        let start = node.key_usage.get(&peer).map_or(0, |usage| usage.used);
+        let end = start + len;
+        Some((key.get(start..end)?, end))
+    }
+
+    /// Checks that both nodes are registered.
+    fn require_nodes(nodes: &HashMap<u32, QuantumNode>, node1: u32, node2: u32) -> Result<(), QuantumApiError> {
+        for id in [node1, node2] {
+            if !nodes.contains_key(&id) {
+                return Err(QuantumApiError::NodeNotFound(id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that both nodes are registered and entangled with each other.
+    fn require_entangled(nodes: &HashMap<u32, QuantumNode>, node1: u32, node2: u32) -> Result<(), QuantumApiError> {
+        Self::require_nodes(nodes, node1, node2)?;
+        if !nodes[&node1].entangled_nodes.contains(&node2) {
+            return Err(QuantumApiError::NotEntangled(node1, node2));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::quantum_cryptography::EncryptionScheme;
+    use crate::core::quantum_packet::MAX_PAYLOAD;
+
+    /// Registers a chain of nodes, entangles neighbours and gives each hop a QKD key.
+    async fn keyed_chain(ids: &[u32]) -> QuantumAPI {
+        let api = QuantumAPI::new();
+        for &id in ids {
+            api.register_node(id).unwrap();
+        }
+        for hop in ids.windows(2) {
+            api.entangle_nodes(hop[0], hop[1]).unwrap();
+            api.exchange_keys(hop[0], hop[1]).await.unwrap();
+        }
+        api
+    }
+
+    #[tokio::test]
+    async fn relay_key_shares_a_key_between_the_endpoints() {
+        let api = keyed_chain(&[1, 2, 3, 4]).await;
+
+        let relay = api.relay_key(&[1, 2, 3, 4]).expect("every hop has a key");
+        assert_eq!(relay.trusted_nodes, vec![2, 3]);
+        assert_eq!(relay.key.len(), RELAY_KEY_LENGTH);
+
+        let comparison = api.compare_keys(1, 4).unwrap();
+        assert!(comparison.keys_match);
+        let nodes = api.read_nodes();
+        assert_eq!(nodes[&1].key_store[&4], relay.key);
+    }
+
+    #[tokio::test]
+    async fn relay_key_does_not_reuse_hop_pad_bytes() {
+        let api = keyed_chain(&[1, 2, 3]).await;
+
+        assert!(api.relay_key(&[1, 2, 3]).is_some());
+        assert!(api.relay_key(&[1, 2, 3]).is_none()); // Each hop key only covers one relay
+    }
+
+    #[tokio::test]
+    async fn verbose_exchange_reflects_the_channel_error_rate() {
+        let mut api = QuantumAPI::new();
+        api.register_node(1).unwrap();
+        api.register_node(2).unwrap();
+        api.entangle_nodes(1, 2).unwrap();
+
+        api.set_channel_error_rate(0.0);
+        let result = api.exchange_keys_verbose(1, 2).await.unwrap();
+        assert_eq!(result.qber, 0.0);
+        assert_eq!(result.key, result.peer_key);
+
+        api.set_channel_error_rate(0.5);
+        assert_eq!(api.exchange_keys_verbose(1, 2).await.unwrap_err(), QuantumApiError::EavesdropperDetected);
+    }
+
+    #[tokio::test]
+    async fn all_zero_keys_are_rejected() {
+        let api = keyed_chain(&[1, 2]).await;
+        let key = api.read_nodes()[&1].key_store[&2].clone();
+
+        assert_eq!(api.store_shared_key(1, 2, &[0u8; 16]).unwrap_err(), QuantumApiError::KeyExchangeFailed);
+        assert_eq!(api.read_nodes()[&1].key_store[&2], key); // The previous key is kept
+        assert_eq!(api.read_nodes()[&2].key_store[&1], key);
+    }
+
+    #[tokio::test]
+    async fn relay_key_rejects_paths_that_revisit_a_node() {
+        let api = keyed_chain(&[1, 2, 3]).await;
+
+        assert!(api.relay_key(&[1, 2, 1]).is_none());
+        assert!(api.relay_key(&[1, 2, 3, 2]).is_none());
+        assert!(api.relay_key(&[2, 2]).is_none());
+        assert!(api.relay_key(&[1, 2, 3]).is_some()); // Rejected paths consumed no pad bytes
+    }
+
+    #[tokio::test]
+    async fn break_entanglement_removes_the_link_and_keys_on_both_sides() {
+        let api = keyed_chain(&[1, 2, 3]).await;
+        {
+            let mut nodes = api.write_nodes();
+            nodes.get_mut(&2).unwrap().state = QuantumState::Entangled(Box::new(QuantumState::Zero));
+            nodes.get_mut(&3).unwrap().next_hop_cache.insert(1, 2);
+        }
+        assert!(api.send_message(1, 2, "hi").is_ok());
+
+        api.break_entanglement(1, 2).unwrap();
+        assert_eq!(
+            api.send_message(1, 2, "hi").unwrap_err(),
+            QuantumApiError::SendFailed(SendFailure::NoKey(2))
+        );
+        assert_eq!(
+            api.send_message(2, 1, "hi").unwrap_err(),
+            QuantumApiError::SendFailed(SendFailure::NoKey(1))
+        );
+        assert_eq!(api.break_entanglement(1, 2).unwrap_err(), QuantumApiError::NotEntangled(1, 2));
+
+        let nodes = api.read_nodes();
+        for (id, peer) in [(1, 2), (2, 1)] {
+            assert!(!nodes[&id].entangled_nodes.contains(&peer));
+            assert!(!nodes[&id].key_store.contains_key(&peer));
+            assert!(!nodes[&id].key_usage.contains_key(&peer));
+        }
+        assert_eq!(nodes[&2].state, QuantumState::Zero);
+        assert!(nodes[&3].next_hop_cache.is_empty());
+        assert!(nodes[&2].key_store.contains_key(&3)); // Other links are untouched
+    }
+
+    #[tokio::test]
+    async fn exchange_keys_rejects_a_self_pair() {
+        let api = QuantumAPI::new();
+        api.register_node(1).unwrap();
+
+        assert_eq!(api.exchange_keys(1, 1).await, Err(QuantumApiError::SelfPair(1)));
+        assert_eq!(api.exchange_keys_verbose(1, 1).await.unwrap_err(), QuantumApiError::SelfPair(1));
+        assert_eq!(api.get_node_status(1).unwrap().key_count, 0);
+    }
+
+    #[tokio::test]
+    async fn compare_keys_reports_matching_and_mismatched_keys() {
+        let api = keyed_chain(&[1, 2]).await;
+        assert_eq!(
+            api.compare_keys(1, 2).unwrap(),
+            KeyComparison {
+                first_has_key: true,
+                second_has_key: true,
+                keys_match: true,
+            }
+        );
+
+        api.write_nodes().get_mut(&2).unwrap().key_store.get_mut(&1).unwrap()[0] ^= 0x01;
+        assert!(!api.compare_keys(1, 2).unwrap().keys_match);
+
+        api.write_nodes().get_mut(&2).unwrap().key_store.remove(&1);
+        assert_eq!(
+            api.compare_keys(2, 1).unwrap(),
+            KeyComparison {
+                first_has_key: false,
+                second_has_key: true,
+                keys_match: false,
+            }
+        );
+        assert_eq!(api.compare_keys(1, 9).unwrap_err(), QuantumApiError::NodeNotFound(9));
+    }
+
+    #[test]
+    fn concurrent_entangle_calls_record_a_single_link() {
+        let api = Arc::new(QuantumAPI::new());
+        api.register_node(1).unwrap();
+        api.register_node(2).unwrap();
+
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let api = Arc::clone(&api);
+                std::thread::spawn(move || if i % 2 == 0 { api.entangle_nodes(1, 2) } else { api.entangle_nodes(2, 1) })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), Ok(()));
+        }
+
+        let nodes = api.read_nodes();
+        assert_eq!(nodes[&1].entangled_nodes, vec![2]);
+        assert_eq!(nodes[&2].entangled_nodes, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn send_message_reports_each_failure_cause() {
+        let api = keyed_chain(&[1, 2]).await;
+        api.register_node(3).unwrap();
+        let key_length = api.read_nodes()[&1].key_store[&2].len();
+
+        assert_eq!(
+            api.send_message(1, 9, "hi").unwrap_err(),
+            QuantumApiError::SendFailed(SendFailure::UnknownReceiver(9))
+        );
+        assert_eq!(api.send_message(1, 3, "hi").unwrap_err(), QuantumApiError::SendFailed(SendFailure::NoKey(3)));
+        assert_eq!(
+            api.send_message(1, 2, &"x".repeat(key_length + 1)).unwrap_err(),
+            QuantumApiError::SendFailed(SendFailure::KeyExhausted {
+                needed: key_length + 1,
+                remaining: key_length,
+            })
+        );
+
+        api.write_nodes().get_mut(&1).unwrap().set_encryption_scheme(EncryptionScheme::AesGcm);
+        let error = api.send_message(1, 2, &"x".repeat(MAX_PAYLOAD)).unwrap_err();
+        assert!(
+            matches!(error, QuantumApiError::SendFailed(SendFailure::PayloadTooLarge { size, max: MAX_PAYLOAD }) if size > MAX_PAYLOAD),
+            "{:?}",
+            error
+        ); // The nonce and tag push the ciphertext over the limit
+        assert_eq!(api.send_message(9, 2, "hi").unwrap_err(), QuantumApiError::NodeNotFound(9));
+    }
+
+    #[tokio::test]
+    async fn send_messages_keeps_batch_order_and_per_item_errors() {
+        let api = keyed_chain(&[1, 2]).await;
+        api.register_node(3).unwrap();
+        let batch = vec![
+            (2, "first".to_string()),
+            (9, "lost".to_string()),
+            (3, "keyless".to_string()),
+            (2, "second".to_string()),
+        ];
+
+        let mut results = api.send_messages(1, &batch);
+        assert_eq!(results.len(), 4);
+        assert_eq!(
+            results[1].as_ref().unwrap_err(),
+            &QuantumApiError::SendFailed(SendFailure::UnknownReceiver(9))
+        );
+        assert_eq!(results[2].as_ref().unwrap_err(), &QuantumApiError::SendFailed(SendFailure::NoKey(3)));
+        let second = results.remove(3).unwrap();
+        let first = results.remove(0).unwrap();
+        assert_eq!(api.receive_message(2, first).as_deref(), Some("first"));
+        assert_eq!(api.receive_message(2, second).as_deref(), Some("second"));
+
+        let orphaned = api.send_messages(9, &batch);
+        assert_eq!(orphaned.len(), 4);
+        assert!(orphaned.iter().all(|result| result.as_ref().err() == Some(&QuantumApiError::NodeNotFound(9))));
     }
 }
@@ -9,7 +9,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use crate::core::api::QuantumAPI;
+use crate::core::api::{PeerKeyInfo, QuantumAPI, QuantumApiError};
 use crate::core::quantum_packet::QuantumPacket;
 
 /// Represents the shared application state.
@@ -51,53 +51,45 @@ pub struct SendMessageRequest {
 pub struct NodeStatusResponse {
     pub entangled_nodes: Vec<u32>,
     pub key_count: usize,
+    pub peer_keys: Vec<PeerKeyInfo>,
 }
 
 /// Handles the registration of a new quantum node.
 pub async fn register_node(
     State(state): State<AppState>,
     AxumJson(payload): AxumJson<RegisterNodeRequest>,
-) -> StatusCode {
-    if state.api.register_node(payload.node_id) {
-        StatusCode::CREATED
-    } else {
-        StatusCode::CONFLICT
-    }
+) -> Result<StatusCode, QuantumApiError> {
+    state.api.register_node(payload.node_id)?;
+    Ok(StatusCode::CREATED)
 }
 
 /// Handles the establishment of quantum entanglement between two nodes.
 pub async fn entangle_nodes(
     State(state): State<AppState>,
     AxumJson(payload): AxumJson<EntangleNodesRequest>,
-) -> StatusCode {
-    if state.api.entangle_nodes(payload.node1, payload.node2) {
-        StatusCode::OK
-    } else {
-        StatusCode::BAD_REQUEST
-    }
+) -> Result<StatusCode, QuantumApiError> {
+    state.api.entangle_nodes(payload.node1, payload.node2)?;
+    Ok(StatusCode::OK)
 }
 
 /// Handles the quantum key distribution (QKD) process.
 pub async fn exchange_keys(
     State(state): State<AppState>,
     AxumJson(payload): AxumJson<KeyExchangeRequest>,
-) -> StatusCode {
-    if state.api.exchange_keys(payload.node1, payload.node2) {
-        StatusCode::OK
-    } else {
-        StatusCode::BAD_REQUEST
-    }
+) -> Result<StatusCode, QuantumApiError> {
+    state.api.exchange_keys(payload.node1, payload.node2).await?;
+    Ok(StatusCode::OK)
 }
 
 /// Handles sending a quantum-secure message.
 pub async fn send_message(
     State(state): State<AppState>,
     AxumJson(payload): AxumJson<SendMessageRequest>,
-) -> Json<Option<QuantumPacket>> {
+) -> Result<Json<QuantumPacket>, QuantumApiError> {
     let packet = state
         .api
-        .send_message(payload.sender_id, payload.receiver_id, &payload.message);
-    Json(packet)
+        .send_message(payload.sender_id, payload.receiver_id, &payload.message)?;
+    Ok(Json(packet))
 }
 
 /// Handles retrieving the status of a quantum node.
@@ -106,8 +98,9 @@ pub async fn get_node_status(
     Path(node_id): Path<u32>,
 ) -> Json<Option<NodeStatusResponse>> {
     let status = state.api.get_node_status(node_id);
-    Json(status.map(|(entangled_nodes, key_count)| NodeStatusResponse {
-        entangled_nodes,
-        key_count,
+    Json(status.map(|status| NodeStatusResponse {
+        entangled_nodes: status.entangled_nodes,
+        key_count: status.key_count,
+        peer_keys: status.peer_keys,
     }))
 }
@@ -0,0 +1,95 @@
+// qkd_limiter.rs - Bounds the number of concurrent QKD operations.
+
+// Purpose of this module: QKD is relatively expensive, so the API caps how many
+// key exchanges run at once. Callers beyond the cap wait for a permit, up to a timeout.
+// Waiting is asynchronous, so a queued exchange never blocks a runtime worker thread.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Default maximum number of key exchanges allowed to run at once.
+pub const DEFAULT_MAX_CONCURRENT_QKD: usize = 8;
+
+/// Default time a key exchange waits for a permit before giving up.
+pub const DEFAULT_QKD_PERMIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A counting semaphore limiting concurrent QKD operations.
+#[derive(Debug, Clone)]
+pub struct QkdLimiter {
+    semaphore: Arc<Semaphore>, // One permit per key exchange allowed to run
+    max_concurrent: usize,     // Maximum number of permits
+    timeout: Duration,         // How long `acquire` waits for a permit
+}
+
+/// A held permit; the slot is released when the permit is dropped.
+#[derive(Debug)]
+pub struct QkdPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl QkdLimiter {
+    /// Creates a limiter.
+    ///
+    /// # Arguments
+    /// * `max_concurrent` - Maximum number of simultaneous key exchanges (at least one).
+    /// * `timeout` - How long a caller waits for a permit.
+    ///
+    /// # Returns
+    /// * `QkdLimiter` - A limiter with no permits in use.
+    pub fn new(max_concurrent: usize, timeout: Duration) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        QkdLimiter {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+            timeout,
+        }
+    }
+
+    /// Waits for a free permit, up to the configured timeout.
+    ///
+    /// # Returns
+    /// * `Some(QkdPermit)` once a slot is free.
+    /// * `None` if the timeout elapsed first.
+    pub async fn acquire(&self) -> Option<QkdPermit> {
+        let permit = tokio::time::timeout(self.timeout, Arc::clone(&self.semaphore).acquire_owned())
+            .await
+            .ok()? // Timed out
+            .ok()?; // The semaphore is never closed, but treat closure as no permit
+        Some(QkdPermit { _permit: permit })
+    }
+
+    /// Returns the number of permits currently held.
+    pub fn in_use(&self) -> usize {
+        self.max_concurrent - self.semaphore.available_permits()
+    }
+}
+
+impl Default for QkdLimiter {
+    fn default() -> Self {
+        QkdLimiter::new(DEFAULT_MAX_CONCURRENT_QKD, DEFAULT_QKD_PERMIT_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn acquire_times_out_while_every_permit_is_held() {
+        let limiter = QkdLimiter::new(2, Duration::from_millis(20));
+
+        let first = limiter.acquire().await.unwrap();
+        let _second = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.in_use(), 2);
+        assert!(limiter.acquire().await.is_none());
+
+        drop(first);
+        assert!(limiter.acquire().await.is_some());
+    }
+
+    #[test]
+    fn limit_is_at_least_one() {
+        assert_eq!(QkdLimiter::new(0, DEFAULT_QKD_PERMIT_TIMEOUT).max_concurrent, 1);
+    }
+}
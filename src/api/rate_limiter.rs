@@ -0,0 +1,117 @@
+// rate_limiter.rs - Per-client request rate limiting for the HTTP API.
+
+// Purpose of this module: Routes such as /qkd and /entangle are relatively expensive,
+// so each client IP gets a token bucket that refills at a steady rate and allows
+// short bursts. Requests arriving with an empty bucket are rejected.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+/// Number of tracked clients above which idle, fully refilled buckets are discarded.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Token-bucket settings applied to every client.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitConfig {
+    pub requests_per_second: f64, // Rate at which a client's bucket refills
+    pub burst: u32,               // Bucket capacity: requests allowed back to back
+}
+
+/// A single client's token bucket.
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,          // Requests currently available
+    last_refill: Instant, // When `tokens` was last brought up to date
+}
+
+/// Per-IP token-bucket rate limiter, shared by all request handlers.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Arc<Mutex<HashMap<IpAddr, Bucket>>>, // Bucket of each client seen so far
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter.
+    ///
+    /// # Arguments
+    /// * `config` - The refill rate and burst size applied to each client.
+    ///
+    /// # Returns
+    /// * `RateLimiter` - A limiter with every client's bucket full.
+    pub fn new(config: RateLimitConfig) -> Self {
+        RateLimiter {
+            config,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Takes one token from a client's bucket, if available.
+    ///
+    /// # Arguments
+    /// * `client` - The IP address of the client making the request.
+    ///
+    /// # Returns
+    /// * `true` if the request is allowed.
+    /// * `false` if the client has exceeded its rate.
+    pub fn try_acquire(&self, client: IpAddr) -> bool {
+        let capacity = f64::from(self.config.burst.max(1));
+        let rate = self.config.requests_per_second.max(0.0);
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.last_refill).as_secs_f64() * rate < capacity
+            });
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+
+    #[test]
+    fn rejects_requests_after_the_burst() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_second: 0.0,
+            burst: 3,
+        });
+
+        assert!((0..3).all(|_| limiter.try_acquire(CLIENT)));
+        assert!(!limiter.try_acquire(CLIENT));
+    }
+
+    #[test]
+    fn tracks_clients_separately() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_second: 0.0,
+            burst: 1,
+        });
+
+        assert!(limiter.try_acquire(CLIENT));
+        assert!(!limiter.try_acquire(CLIENT));
+        assert!(limiter.try_acquire(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2))));
+    }
+}
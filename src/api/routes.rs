@@ -1,17 +1,55 @@
 // routes.rs - Defines API endpoints for interacting with the Quantum Network.
 
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    body::{self, Body},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, Path, Query, Request, State,
+    },
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json as AxumJson, Router,
 };
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tower_http::catch_panic::CatchPanicLayer;
 
-use crate::core::api::QuantumAPI;
+use crate::core::api::{PeerKeyInfo, QuantumAPI, QuantumApiError};
 use crate::core::quantum_packet::QuantumPacket;
+use crate::core::rate_limiter::{RateLimitConfig, RateLimiter};
+
+/// Default prefix under which the versioned API is served.
+pub const DEFAULT_API_PREFIX: &str = "/v1";
+
+/// Configuration options for the API router.
+#[derive(Clone, Debug)]
+pub struct RouterConfig {
+    pub api_prefix: String, // Prefix for versioned routes, e.g. "/v1"; "" or "/" serves them unprefixed
+    pub legacy_routes: bool, // Also serve unprefixed routes (deprecated, removed next release)
+    pub debug_routes: bool, // Serve privileged diagnostic routes such as /key_compare
+    pub rate_limit: Option<RateLimitConfig>, // Per-client request rate limit (None = unlimited)
+    pub auth_token: Option<String>, // Bearer token required by protected routes (None = open API)
+    pub public_status_routes: bool, // Leave GET status routes open when `auth_token` is set
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        RouterConfig {
+            api_prefix: DEFAULT_API_PREFIX.to_string(),
+            legacy_routes: true,
+            debug_routes: false,
+            rate_limit: None,
+            auth_token: None,
+            public_status_routes: false,
+        }
+    }
+}
 
 /// Represents the shared state of the API.
 #[derive(Clone)]
@@ -25,6 +63,13 @@ struct RegisterNodeRequest {
     node_id: u32,
 }
 
+/// Query parameters for paginating the node list.
+#[derive(Deserialize)]
+struct NodeListQuery {
+    offset: Option<usize>, // Number of IDs to skip
+    limit: Option<usize>,  // Maximum number of IDs to return
+}
+
 /// Defines the structure of a request for entangling two nodes.
 #[derive(Deserialize)]
 struct EntangleNodesRequest {
@@ -39,6 +84,28 @@ struct KeyExchangeRequest {
     node2: u32,
 }
 
+/// Query parameters for the key exchange route.
+#[derive(Deserialize)]
+struct KeyExchangeQuery {
+    #[serde(default)]
+    verbose: bool, // Return the BB84 sift details instead of a bare status
+}
+
+/// Defines the structure of a verbose key exchange response.
+#[derive(Serialize)]
+struct KeyExchangeResponse {
+    key_bits: usize,    // Length of the sifted key in bits
+    sifted_bits: usize, // Bits kept after basis sifting, before QBER sampling
+    qber: f64,          // Estimated quantum bit error rate
+}
+
+/// Defines the structure of a QKD response carrying the generated key.
+#[derive(Serialize)]
+struct QkdResponse {
+    key: String, // Base64-encoded key
+    qber: f64,   // Estimated quantum bit error rate
+}
+
 /// Defines the structure of a message-sending request.
 #[derive(Deserialize)]
 struct SendMessageRequest {
@@ -47,81 +114,654 @@ struct SendMessageRequest {
     message: String,
 }
 
+/// Defines the structure of a message-receiving request.
+#[derive(Deserialize)]
+struct ReceiveMessageRequest {
+    receiver_id: u32,
+    packet: QuantumPacket,
+}
+
+/// Defines the structure of a response carrying a decrypted message.
+#[derive(Serialize)]
+struct ReceiveMessageResponse {
+    message: String,
+}
+
+/// Query parameters for the key comparison route.
+#[derive(Deserialize)]
+struct KeyCompareQuery {
+    a: u32,
+    b: u32,
+}
+
+/// Defines the structure of a key comparison response.
+#[derive(Serialize)]
+struct KeyCompareResponse {
+    a_has_key: bool,  // Node `a` stores a key for node `b`
+    b_has_key: bool,  // Node `b` stores a key for node `a`
+    keys_match: bool, // Both keys exist and are identical
+}
+
 /// Defines the structure of a response for node status.
 #[derive(Serialize)]
 struct NodeStatusResponse {
     entangled_nodes: Vec<u32>,
     key_count: usize,
+    peer_keys: Vec<PeerKeyInfo>, // Key fingerprints per peer; never the keys themselves
+}
+
+/// Defines the structure of an error response body.
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+impl IntoResponse for QuantumApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            QuantumApiError::NodeAlreadyExists(_) => StatusCode::CONFLICT,
+            QuantumApiError::NodeNotFound(_) => StatusCode::NOT_FOUND,
+            QuantumApiError::SelfPair(_) => StatusCode::BAD_REQUEST,
+            QuantumApiError::NotEntangled(_, _) => StatusCode::BAD_REQUEST,
+            QuantumApiError::EntanglementFailed(_, _) => StatusCode::SERVICE_UNAVAILABLE,
+            QuantumApiError::EavesdropperDetected => StatusCode::CONFLICT,
+            QuantumApiError::InsufficientKeyMaterial => StatusCode::UNPROCESSABLE_ENTITY,
+            QuantumApiError::KeyExchangeFailed => StatusCode::INTERNAL_SERVER_ERROR,
+            QuantumApiError::QkdBusy => StatusCode::SERVICE_UNAVAILABLE,
+            QuantumApiError::SendFailed(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        let body = ErrorResponse {
+            error: self.to_string(),
+        };
+        (status, AxumJson(body)).into_response()
+    }
+}
+
+/// Query parameters controlling how JSON responses are formatted.
+#[derive(Deserialize)]
+struct FormatQuery {
+    #[serde(default)]
+    pretty: bool, // Emit indented JSON when true
 }
 
 /// Registers a new quantum node.
 async fn register_node(
     State(state): State<AppState>,
     AxumJson(payload): AxumJson<RegisterNodeRequest>,
-) -> StatusCode {
-    if state.api.register_node(payload.node_id) {
-        StatusCode::CREATED
-    } else {
-        StatusCode::CONFLICT
-    }
+) -> Result<StatusCode, QuantumApiError> {
+    state.api.register_node(payload.node_id)?;
+    Ok(StatusCode::CREATED)
+}
+
+/// Lists registered node IDs in ascending order, optionally paginated.
+async fn list_nodes(
+    State(state): State<AppState>,
+    Query(query): Query<NodeListQuery>,
+) -> AxumJson<Vec<u32>> {
+    let ids = state
+        .api
+        .list_nodes()
+        .into_iter()
+        .skip(query.offset.unwrap_or(0))
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+    AxumJson(ids)
 }
 
 /// Establishes entanglement between two nodes.
 async fn entangle_nodes(
     State(state): State<AppState>,
     AxumJson(payload): AxumJson<EntangleNodesRequest>,
-) -> StatusCode {
-    if state.api.entangle_nodes(payload.node1, payload.node2) {
-        StatusCode::OK
-    } else {
-        StatusCode::BAD_REQUEST
-    }
+) -> Result<StatusCode, QuantumApiError> {
+    state.api.entangle_nodes(payload.node1, payload.node2)?;
+    Ok(StatusCode::OK)
+}
+
+/// Breaks the entanglement between two nodes.
+async fn break_entanglement(
+    State(state): State<AppState>,
+    AxumJson(payload): AxumJson<EntangleNodesRequest>,
+) -> Result<StatusCode, QuantumApiError> {
+    state.api.break_entanglement(payload.node1, payload.node2)?;
+    Ok(StatusCode::OK)
 }
 
 /// Initiates Quantum Key Distribution (QKD).
+///
+/// With `?verbose=true` the exchange runs BB84 and returns the sift outcome.
 async fn exchange_keys(
     State(state): State<AppState>,
+    Query(query): Query<KeyExchangeQuery>,
     AxumJson(payload): AxumJson<KeyExchangeRequest>,
-) -> StatusCode {
-    if state.api.exchange_keys(payload.node1, payload.node2) {
-        StatusCode::OK
-    } else {
-        StatusCode::BAD_REQUEST
+) -> Result<Response, QuantumApiError> {
+    if !query.verbose {
+        state.api.exchange_keys(payload.node1, payload.node2).await?;
+        return Ok(StatusCode::OK.into_response());
     }
+
+    let result = state.api.exchange_keys_verbose(payload.node1, payload.node2).await?;
+    let body = KeyExchangeResponse {
+        key_bits: result.key_bits,
+        sifted_bits: result.sifted_bits,
+        qber: result.qber,
+    };
+    Ok((StatusCode::OK, AxumJson(body)).into_response())
+}
+
+/// Runs QKD between two nodes and returns the key material.
+async fn perform_qkd(
+    State(state): State<AppState>,
+    AxumJson(payload): AxumJson<KeyExchangeRequest>,
+) -> Result<AxumJson<QkdResponse>, QuantumApiError> {
+    let (key, qber) = state.api.perform_qkd(payload.node1, payload.node2).await?;
+    Ok(AxumJson(QkdResponse {
+        key: STANDARD.encode(key),
+        qber,
+    }))
 }
 
 /// Sends a quantum-secure message.
 async fn send_message(
     State(state): State<AppState>,
     AxumJson(payload): AxumJson<SendMessageRequest>,
-) -> Json<Option<QuantumPacket>> {
+) -> Result<AxumJson<QuantumPacket>, QuantumApiError> {
     let packet = state
         .api
-        .send_message(payload.sender_id, payload.receiver_id, &payload.message);
-    Json(packet)
+        .send_message(payload.sender_id, payload.receiver_id, &payload.message)?;
+    Ok(AxumJson(packet))
+}
+
+/// Receives and decrypts a quantum-secure message.
+///
+/// Responds with `400 Bad Request` if the packet cannot be decrypted.
+async fn receive_message(
+    State(state): State<AppState>,
+    AxumJson(payload): AxumJson<ReceiveMessageRequest>,
+) -> Response {
+    match state.api.receive_message(payload.receiver_id, payload.packet) {
+        Some(message) => (StatusCode::OK, AxumJson(ReceiveMessageResponse { message })).into_response(),
+        None => {
+            let body = ErrorResponse {
+                error: "Failed to decrypt message.".to_string(),
+            };
+            (StatusCode::BAD_REQUEST, AxumJson(body)).into_response()
+        }
+    }
 }
 
 /// Retrieves the status of a quantum node.
 async fn get_node_status(
     State(state): State<AppState>,
     Path(node_id): Path<u32>,
-) -> Json<Option<NodeStatusResponse>> {
-    let status = state.api.get_node_status(node_id);
-    Json(status.map(|(entangled_nodes, key_count)| NodeStatusResponse {
-        entangled_nodes,
-        key_count,
+) -> AxumJson<Option<NodeStatusResponse>> {
+    AxumJson(node_status(&state.api, node_id))
+}
+
+/// Upgrades to a WebSocket that pushes a node's status whenever it changes.
+async fn status_socket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Path(node_id): Path<u32>,
+) -> Response {
+    ws.on_upgrade(move |socket| stream_status(socket, state.api, node_id))
+}
+
+/// Sends the node's current status, then a fresh status after every change to it.
+///
+/// The stream ends when the client closes the socket or the connection fails.
+async fn stream_status(mut socket: WebSocket, api: Arc<QuantumAPI>, node_id: u32) {
+    let mut updates = api.subscribe_status();
+    let mut changed = true; // Send the initial status on connect
+
+    loop {
+        if changed {
+            let body = match serde_json::to_string(&node_status(&api, node_id)) {
+                Ok(body) => body,
+                Err(_) => break,
+            };
+            if socket.send(Message::Text(body.into())).await.is_err() {
+                break; // Client disconnected
+            }
+        }
+
+        changed = tokio::select! {
+            update = updates.recv() => match update {
+                Ok(id) => id == node_id,
+                Err(RecvError::Lagged(_)) => true, // Missed notifications may have included this node
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break, // Client disconnected
+                Some(Ok(_)) => false, // Other client messages are ignored
+            },
+        };
+    }
+}
+
+/// Builds the status response body for a node.
+fn node_status(api: &QuantumAPI, node_id: u32) -> Option<NodeStatusResponse> {
+    api.get_node_status(node_id).map(|status| NodeStatusResponse {
+        entangled_nodes: status.entangled_nodes,
+        key_count: status.key_count,
+        peer_keys: status.peer_keys,
+    })
+}
+
+/// Reports whether two nodes hold matching keys for each other, without revealing them.
+///
+/// Only served when `RouterConfig::debug_routes` is enabled.
+async fn key_compare(
+    State(state): State<AppState>,
+    Query(query): Query<KeyCompareQuery>,
+) -> Result<AxumJson<KeyCompareResponse>, QuantumApiError> {
+    let comparison = state.api.compare_keys(query.a, query.b)?;
+    Ok(AxumJson(KeyCompareResponse {
+        a_has_key: comparison.first_has_key,
+        b_has_key: comparison.second_has_key,
+        keys_match: comparison.keys_match,
     }))
 }
 
-/// Sets up the router and defines all API routes.
+/// Converts a handler panic into a generic JSON `500` response.
+///
+/// The panic message is never returned to the client; the process's panic hook
+/// has already reported it.
+fn handle_panic(_err: Box<dyn Any + Send + 'static>) -> Response {
+    let body = ErrorResponse {
+        error: "Internal server error".to_string(),
+    };
+    (StatusCode::INTERNAL_SERVER_ERROR, AxumJson(body)).into_response()
+}
+
+/// Rejects requests from clients that have exceeded their rate limit.
+///
+/// Clients are identified by IP address, which requires serving the router with
+/// `into_make_service_with_connect_info::<SocketAddr>()`; otherwise all requests
+/// share a single bucket.
+async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = connect_info.map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |ConnectInfo(addr)| addr.ip());
+    if !limiter.try_acquire(client) {
+        let body = ErrorResponse {
+            error: "Too many requests".to_string(),
+        };
+        return (StatusCode::TOO_MANY_REQUESTS, AxumJson(body)).into_response();
+    }
+    next.run(request).await
+}
+
+/// Rejects requests that do not carry `Authorization: Bearer <token>` with the configured token.
+async fn require_token(State(token): State<Arc<str>>, request: Request, next: Next) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    // Compare without short-circuiting so timing does not reveal matching prefixes
+    let authorized = presented.is_some_and(|presented| {
+        presented.len() == token.len()
+            && presented.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+    });
+    if !authorized {
+        let body = ErrorResponse {
+            error: "Missing or invalid bearer token".to_string(),
+        };
+        return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")], AxumJson(body)).into_response();
+    }
+    next.run(request).await
+}
+
+/// Re-indents JSON response bodies when the request carries `?pretty=true`.
+///
+/// Applies to every JSON response, including errors. Object keys come out in
+/// sorted order in pretty-printed bodies.
+async fn pretty_json(request: Request, next: Next) -> Response {
+    let pretty = Query::<FormatQuery>::try_from_uri(request.uri()).is_ok_and(|Query(format)| format.pretty);
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if !pretty || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let formatted = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .and_then(|value| serde_json::to_vec_pretty(&value))
+        .map(Into::into)
+        .unwrap_or(bytes); // Leave bodies that are not valid JSON untouched
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(formatted))
+}
+
+/// Sets up the router with the default configuration.
 pub fn create_router(api: Arc<QuantumAPI>) -> Router {
+    create_router_with_config(api, RouterConfig::default())
+}
+
+/// Sets up the router and defines all API routes.
+///
+/// Routes are served under `config.api_prefix`, with a missing leading `/` added. When
+/// `config.legacy_routes` is set, they are also served unprefixed as deprecated aliases.
+/// Diagnostic routes are only added when `config.debug_routes` is set. When `config.rate_limit` is set, clients
+/// exceeding it receive `429 Too Many Requests`. When `config.auth_token` is set, requests
+/// without that bearer token receive `401 Unauthorized`; the GET status routes stay open
+/// if `config.public_status_routes` is also set. Any JSON response is pretty-printed
+/// when the request carries `?pretty=true`.
+pub fn create_router_with_config(api: Arc<QuantumAPI>, config: RouterConfig) -> Router {
     let state = AppState { api };
 
-    Router::new()
+    let prefix = normalize_prefix(&config.api_prefix);
+    let routes = || api_routes(&config);
+    let router = if prefix.is_empty() {
+        routes()
+    } else if config.legacy_routes {
+        Router::new().nest(&prefix, routes()).merge(routes())
+    } else {
+        Router::new().nest(&prefix, routes())
+    };
+
+    let router = match config.rate_limit {
+        Some(rate_limit_config) => {
+            router.layer(middleware::from_fn_with_state(RateLimiter::new(rate_limit_config), rate_limit))
+        }
+        None => router,
+    };
+
+    router
+        .layer(CatchPanicLayer::custom(handle_panic))
+        .layer(middleware::from_fn(pretty_json))
+        .with_state(state)
+}
+
+/// Normalizes a route prefix to a leading `/` and no trailing `/`.
+///
+/// Returns an empty string for an empty prefix or `/`, meaning routes are not nested.
+fn normalize_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
+
+/// Defines all API routes relative to the mount point.
+fn api_routes(config: &RouterConfig) -> Router<AppState> {
+    let mut protected_routes = Router::new()
         .route("/register", post(register_node))
         .route("/entangle", post(entangle_nodes))
+        .route("/break_entanglement", post(break_entanglement))
         .route("/exchange_keys", post(exchange_keys))
+        .route("/qkd", post(perform_qkd))
         .route("/send_message", post(send_message))
+        .route("/receive_message", post(receive_message));
+    if config.debug_routes {
+        protected_routes = protected_routes.route("/key_compare", get(key_compare));
+    }
+
+    let status_routes = Router::new()
+        .route("/nodes", get(list_nodes))
         .route("/node_status/:node_id", get(get_node_status))
-        .with_state(state)
+        .route("/ws/status/:node_id", get(status_socket));
+
+    match &config.auth_token {
+        Some(token) => {
+            let auth = middleware::from_fn_with_state(Arc::<str>::from(token.as_str()), require_token);
+            let status_routes = if config.public_status_routes {
+                status_routes
+            } else {
+                status_routes.route_layer(auth.clone())
+            };
+            protected_routes.route_layer(auth).merge(status_routes)
+        }
+        None => protected_routes.merge(status_routes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request as HttpRequest;
+    use tower::ServiceExt;
+
+    /// Sends an empty request and returns the response status.
+    async fn status_of(router: &Router, method: &str, uri: &str) -> StatusCode {
+        status_with_token(router, method, uri, None).await
+    }
+
+    /// Sends an empty request, optionally with a bearer token, and returns the response status.
+    async fn status_with_token(router: &Router, method: &str, uri: &str, token: Option<&str>) -> StatusCode {
+        let mut request = HttpRequest::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = request.body(Body::empty()).unwrap();
+        router.clone().oneshot(request).await.unwrap().status()
+    }
+
+    /// Sends a request with an optional JSON body and returns the status and response body.
+    async fn send_json(router: &Router, method: &str, uri: &str, json: Option<serde_json::Value>) -> (StatusCode, String) {
+        let request = HttpRequest::builder().method(method).uri(uri);
+        let request = match json {
+            Some(json) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json.to_string())),
+            None => request.body(Body::empty()),
+        };
+        let response = router.clone().oneshot(request.unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    /// Builds a default router over two registered, entangled nodes, 1 and 2.
+    fn entangled_router() -> Router {
+        let api = QuantumAPI::new();
+        api.register_node(1).unwrap();
+        api.register_node(2).unwrap();
+        api.entangle_nodes(1, 2).unwrap();
+        create_router(Arc::new(api))
+    }
+
+    /// Builds a router that requires the bearer token "secret".
+    fn authenticated_router(public_status_routes: bool) -> Router {
+        let config = RouterConfig {
+            auth_token: Some("secret".to_string()),
+            public_status_routes,
+            ..RouterConfig::default()
+        };
+        create_router_with_config(Arc::new(QuantumAPI::new()), config)
+    }
+
+    #[tokio::test]
+    async fn rate_limit_answers_429_after_the_burst() {
+        let config = RouterConfig {
+            rate_limit: Some(RateLimitConfig {
+                requests_per_second: 0.0,
+                burst: 2,
+            }),
+            ..RouterConfig::default()
+        };
+        let router = create_router_with_config(Arc::new(QuantumAPI::new()), config);
+
+        assert_eq!(status_of(&router, "GET", "/v1/nodes").await, StatusCode::OK);
+        assert_eq!(status_of(&router, "GET", "/v1/nodes").await, StatusCode::OK);
+        assert_eq!(status_of(&router, "GET", "/v1/nodes").await, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn missing_or_invalid_token_answers_401() {
+        let router = authenticated_router(false);
+
+        assert_eq!(status_of(&router, "POST", "/v1/register").await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status_with_token(&router, "GET", "/v1/nodes", Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn valid_token_passes_through() {
+        let router = authenticated_router(false);
+
+        assert_eq!(status_with_token(&router, "GET", "/v1/nodes", Some("secret")).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn public_status_routes_skip_authentication() {
+        let router = authenticated_router(true);
+
+        assert_eq!(status_of(&router, "GET", "/v1/nodes").await, StatusCode::OK);
+        assert_eq!(status_of(&router, "POST", "/v1/register").await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn verbose_key_exchange_reports_the_sift_outcome() {
+        let router = entangled_router();
+        let pair = serde_json::json!({ "node1": 1, "node2": 2 });
+
+        let (status, body) = send_json(&router, "POST", "/v1/exchange_keys", Some(pair.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_empty());
+
+        let (status, body) = send_json(&router, "POST", "/v1/exchange_keys?verbose=true", Some(pair)).await;
+        assert_eq!(status, StatusCode::OK);
+        let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(response["key_bits"].as_u64().unwrap() > 0);
+        assert!(response["sifted_bits"].as_u64().unwrap() >= response["key_bits"].as_u64().unwrap());
+        assert!(response["qber"].as_f64().is_some());
+    }
+
+
+    #[tokio::test]
+    async fn pretty_query_indents_json_responses() {
+        let router = entangled_router();
+
+        let (status, compact) = send_json(&router, "GET", "/v1/node_status/1", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!compact.contains('\n'));
+
+        let (status, pretty) = send_json(&router, "GET", "/v1/node_status/1?pretty=true", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(pretty.contains("\n  \""));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap()
+        );
+
+        // Errors are pretty-printed too
+        let duplicate = serde_json::json!({ "node_id": 1 });
+        let (status, error) = send_json(&router, "POST", "/v1/register?pretty=true", Some(duplicate)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(error.starts_with("{\n  \"error\""));
+    }
+
+
+    #[tokio::test]
+    async fn panicking_handler_answers_500_json() {
+        let router = Router::new()
+            .route("/panic", get(|| async { panic!("handler bug with secret details") }))
+            .layer(CatchPanicLayer::custom(handle_panic));
+
+        let (status, body) = send_json(&router, "GET", "/panic", None).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(response, serde_json::json!({ "error": "Internal server error" }));
+        assert!(!body.contains("secret")); // The panic message is not leaked
+    }
+
+
+    #[tokio::test]
+    async fn legacy_routes_mirror_the_versioned_routes() {
+        let router = entangled_router();
+
+        for uri in ["/nodes", "/node_status/1", "/node_status/7"] {
+            let versioned = send_json(&router, "GET", &format!("/v1{}", uri), None).await;
+            let legacy = send_json(&router, "GET", uri, None).await;
+            assert_eq!(versioned.0, StatusCode::OK, "{}", uri);
+            assert_eq!(versioned, legacy, "{}", uri);
+        }
+
+        let pair = serde_json::json!({ "node1": 1, "node2": 2 });
+        assert_eq!(send_json(&router, "POST", "/v1/exchange_keys", Some(pair.clone())).await.0, StatusCode::OK);
+        assert_eq!(send_json(&router, "POST", "/exchange_keys", Some(pair)).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn legacy_routes_can_be_disabled() {
+        let config = RouterConfig {
+            legacy_routes: false,
+            ..RouterConfig::default()
+        };
+        let router = create_router_with_config(Arc::new(QuantumAPI::new()), config);
+
+        assert_eq!(status_of(&router, "GET", "/v1/nodes").await, StatusCode::OK);
+        assert_eq!(status_of(&router, "GET", "/nodes").await, StatusCode::NOT_FOUND);
+        assert_eq!(status_of(&router, "POST", "/register").await, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn api_prefix_is_normalized() {
+        assert_eq!(normalize_prefix("v2/"), "/v2");
+        assert_eq!(normalize_prefix(" /v2 "), "/v2");
+        assert_eq!(normalize_prefix("/"), "");
+
+        let config = RouterConfig {
+            api_prefix: "v2/".to_string(),
+            legacy_routes: false,
+            ..RouterConfig::default()
+        };
+        let router = create_router_with_config(Arc::new(QuantumAPI::new()), config);
+        assert_eq!(status_of(&router, "GET", "/v2/nodes").await, StatusCode::OK);
+    }
+
+
+    #[tokio::test]
+    async fn self_pair_key_exchange_answers_400() {
+        let router = entangled_router();
+        let pair = serde_json::json!({ "node1": 1, "node2": 1 });
+
+        let (status, body) = send_json(&router, "POST", "/v1/exchange_keys", Some(pair)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("cannot be paired with itself"));
+    }
+
+
+    #[tokio::test]
+    async fn key_compare_is_served_only_in_debug_mode() {
+        let api = Arc::new(QuantumAPI::new());
+        for id in [1, 2, 3] {
+            api.register_node(id).unwrap();
+        }
+        api.entangle_nodes(1, 2).unwrap();
+        api.exchange_keys(1, 2).await.unwrap();
+        let config = RouterConfig {
+            debug_routes: true,
+            ..RouterConfig::default()
+        };
+        let router = create_router_with_config(Arc::clone(&api), config);
+
+        let (status, body) = send_json(&router, "GET", "/v1/key_compare?a=1&b=2", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(response, serde_json::json!({ "a_has_key": true, "b_has_key": true, "keys_match": true }));
+
+        let (_, body) = send_json(&router, "GET", "/v1/key_compare?a=1&b=3", None).await;
+        let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(response, serde_json::json!({ "a_has_key": false, "b_has_key": false, "keys_match": false }));
+
+        assert_eq!(status_of(&create_router(api), "GET", "/v1/key_compare?a=1&b=2").await, StatusCode::NOT_FOUND);
+    }
+
 }
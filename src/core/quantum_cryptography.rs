@@ -3,9 +3,122 @@
 // Purpose of this module: Provides quantum cryptographic methods, including
 // Quantum Key Distribution (QKD) and quantum-secure encryption mechanisms.
 
-use crate::core::quantum_network::{QuantumNode, QuantumNetwork};
+use crate::core::quantum_network::{QuantumNetwork, QuantumState};
+use crate::core::quantum_node::QuantumNode;
 use crate::core::quantum_entanglement::QuantumEntanglement;
-use rand::{Rng, seq::SliceRandom};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::ChaCha20;
+use hkdf::Hkdf;
+use rand::{Rng, RngCore, SeedableRng, seq::SliceRandom};
+use rand::rngs::StdRng;
+use sha2::Sha256;
+use std::f64::consts::PI;
+use std::io::{self, Read, Write};
+
+/// Size in bytes of each chunk processed by the streaming cipher.
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Default minimum fraction of set (and of unset) bits a generated key must contain.
+pub const MIN_KEY_WEIGHT_RATIO: f64 = 0.25;
+
+/// Length in bytes of keys produced by `quantum_key_distribution`.
+pub const QKD_KEY_LENGTH: usize = 16;
+
+/// Fraction of sifted BB84 bits disclosed to estimate the QBER.
+pub const BB84_SAMPLE_FRACTION: f64 = 0.25;
+
+/// Block size, in bits, used by each reconciliation pass.
+const CASCADE_BLOCK_BITS: usize = 8;
+
+/// Consecutive error-free passes after which reconciliation stops.
+const CASCADE_CLEAN_PASSES: usize = 8;
+
+/// Upper bound on reconciliation passes.
+const CASCADE_MAX_PASSES: usize = 64;
+
+/// Length in bytes of the random nonce prepended to AES-GCM ciphertexts.
+pub const AES_GCM_NONCE_LENGTH: usize = 12;
+
+/// HKDF context string binding derived keys to their use as AES-256-GCM keys.
+const AES_GCM_KDF_INFO: &[u8] = b"QuantumNet AES-256-GCM";
+
+/// HKDF context string binding a derived key to its use as the packet MAC key.
+const MAC_KDF_INFO: &[u8] = b"QuantumNet HMAC-SHA256 packet MAC";
+
+/// HKDF context string binding a derived key to its use as the stream cipher key.
+const STREAM_KDF_INFO: &[u8] = b"QuantumNet ChaCha20 stream key";
+
+/// HKDF context prefix for per-chunk stream cipher nonces; the chunk counter follows it.
+const STREAM_NONCE_KDF_INFO: &[u8] = b"QuantumNet ChaCha20 chunk nonce";
+
+/// HKDF context string binding a derived key to its use as a shared QKD key.
+const SHARED_KEY_KDF_INFO: &[u8] = b"QuantumNet QKD shared key";
+
+/// Seed of the public Toeplitz matrix used by `privacy_amplify`.
+pub const DEFAULT_AMPLIFICATION_SEED: u64 = 0x5EED;
+
+/// Polarizer angles Alice chooses between in E91 (0, π/8, π/4).
+const E91_ALICE_ANGLES: [f64; 3] = [0.0, PI / 8.0, PI / 4.0];
+
+/// Polarizer angles Bob chooses between in E91 (π/8, π/4, 3π/8).
+const E91_BOB_ANGLES: [f64; 3] = [PI / 8.0, PI / 4.0, 3.0 * PI / 8.0];
+
+/// How a node encrypts the messages it sends with a shared QKD key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EncryptionScheme {
+    #[default]
+    OneTimePad, // XOR with unused key bytes; messages are limited by the remaining key
+    AesGcm,     // AES-256-GCM under a key derived from the QKD key; no length limit
+}
+
+/// Errors that can occur during quantum key distribution.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QkdError {
+    NodeNotFound(u32), // The referenced node does not exist
+    NotEntangled,      // QKD requires the nodes to be entangled
+    StaleEntanglement, // The entangled pair is older than the freshness limit
+    EavesdropperDetected, // Estimated QBER exceeded the detection threshold
+    InsufficientKeyMaterial, // Too few bits survived sifting to form a key
+    WeakKey,           // The generated key failed the randomness sanity check
+    SelfPair,          // A node cannot exchange keys with itself
+}
+
+/// Errors that can occur during encryption or decryption.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CryptoError {
+    KeyTooShort { needed: usize, got: usize }, // One-time pad needs one key byte per message byte
+    InvalidPlaintext,                          // Decrypted bytes are not valid UTF-8
+    AuthenticationFailed,                      // AES-GCM ciphertext was tampered with or truncated
+}
+
+/// Outcome of a BB84 key exchange.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bb84Result {
+    pub key: Vec<u8>,       // Sender's sifted key (bits packed MSB-first)
+    pub peer_key: Vec<u8>,  // Receiver's sifted key; differs from `key` where errors occurred
+    pub key_bits: usize,    // Number of valid bits in the keys
+    pub sifted_bits: usize, // Bits kept after basis sifting, before QBER sampling
+    pub qber: f64,          // Estimated quantum bit error rate
+}
+
+/// Outcome of an E91 key exchange.
+#[derive(Debug, Clone, PartialEq)]
+pub struct E91Result {
+    pub key: Vec<u8>,      // Alice's key from matched-angle measurements (bits packed MSB-first)
+    pub peer_key: Vec<u8>, // Bob's key; equals `key` for a genuinely entangled link
+    pub key_bits: usize,   // Number of valid bits in the keys
+    pub chsh: f64,         // CHSH value |S|; above 2 only for genuine entanglement
+}
+
+/// Bit-level differences between two keys, used for reconciliation and QBER estimation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyDiff {
+    pub differing_bits: Vec<usize>, // Positions (MSB-first across bytes) where the keys differ
+    pub hamming_distance: usize,    // Number of differing bits
+}
 
 /// A structure that handles quantum cryptographic operations.
 pub struct QuantumCryptography;
@@ -19,17 +132,141 @@ impl QuantumCryptography {
     /// * `node_id_2` - The ID of the second node.
     ///
     /// # Returns
+    /// * `Ok(Vec<u8>)` containing the secure quantum key if successful; both nodes also
+    ///   store it for each other.
+    /// * `Err(QkdError::SelfPair)` if both IDs refer to the same node.
+    /// * `Err(QkdError::StaleEntanglement)` if the pair must be re-entangled first.
+    /// * `Err(QkdError::WeakKey)` if the key fails the Hamming-weight check.
+    /// * `Err(QkdError)` if a node is missing or the nodes are not entangled.
+    pub fn quantum_key_distribution(network: &mut QuantumNetwork, node_id_1: u32, node_id_2: u32) -> Result<Vec<u8>, QkdError> {
+        Self::quantum_key_distribution_with_min_weight(network, node_id_1, node_id_2, MIN_KEY_WEIGHT_RATIO)
+    }
+
+    /// Runs QKD like `quantum_key_distribution` with a custom key-weight threshold.
+    ///
+    /// # Arguments
+    /// * `network` - The mutable reference to the quantum network.
+    /// * `node_id_1` - The ID of the first node.
+    /// * `node_id_2` - The ID of the second node.
+    /// * `min_weight_ratio` - Minimum fraction of set and of unset bits in the key.
+    ///
+    /// # Returns
     /// * `Ok(Vec<u8>)` containing the secure quantum key if successful.
-    /// * `Err(String)` if key exchange fails.
-    pub fn quantum_key_distribution(network: &mut QuantumNetwork, node_id_1: u32, node_id_2: u32) -> Result<Vec<u8>, String> {
+    /// * `Err(QkdError)` as for `quantum_key_distribution`.
+    pub fn quantum_key_distribution_with_min_weight(
+        network: &mut QuantumNetwork,
+        node_id_1: u32,
+        node_id_2: u32,
+        min_weight_ratio: f64,
+    ) -> Result<Vec<u8>, QkdError> {
+        Self::check_qkd_pair(network, node_id_1, node_id_2)?;
+
+        let rng = network.rng();
+        let key = Self::generate_shared_key(rng.gen(), rng.gen());
+        Self::check_key_weight(&key, min_weight_ratio)?;
+
+        // Both parties store the same key
+        for (id, peer) in [(node_id_1, node_id_2), (node_id_2, node_id_1)] {
+            if let Some(node) = network.get_node_mut(id) {
+                node.exchange_keys(peer, &key);
+            }
+        }
+        network.touch_link(node_id_1, node_id_2);
+        Ok(key)
+    }
+
+    /// Checks that two nodes may run QKD with each other.
+    ///
+    /// # Arguments
+    /// * `network` - The quantum network.
+    /// * `node_id_1` - The ID of the first node.
+    /// * `node_id_2` - The ID of the second node.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the nodes are distinct, present, entangled and the pair is fresh.
+    /// * `Err(QkdError)` naming the first check that failed.
+    fn check_qkd_pair(network: &QuantumNetwork, node_id_1: u32, node_id_2: u32) -> Result<(), QkdError> {
+        if node_id_1 == node_id_2 {
+            return Err(QkdError::SelfPair);
+        }
+
         if !QuantumEntanglement::are_entangled(
-            network.get_node(node_id_1).ok_or("Node 1 not found")?,
-            network.get_node(node_id_2).ok_or("Node 2 not found")?,
+            network.get_node(node_id_1).ok_or(QkdError::NodeNotFound(node_id_1))?,
+            network.get_node(node_id_2).ok_or(QkdError::NodeNotFound(node_id_2))?,
         ) {
-            return Err("Nodes are not entangled. QKD requires entanglement.".to_string());
+            return Err(QkdError::NotEntangled);
+        }
+
+        if !network.is_entanglement_fresh(node_id_1, node_id_2) {
+            return Err(QkdError::StaleEntanglement);
         }
+        Ok(())
+    }
 
-        let mut rng = rand::thread_rng();
+    /// Rejects keys whose Hamming weight is implausibly low or high for random data.
+    ///
+    /// This is a cheap sanity check against a broken RNG (e.g. an all-zero key),
+    /// not a statistical randomness test.
+    ///
+    /// # Arguments
+    /// * `key` - The key to check.
+    /// * `min_weight_ratio` - Minimum fraction of set and of unset bits.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the key passes.
+    /// * `Err(QkdError::WeakKey)` if the key is empty or too unbalanced.
+    pub fn check_key_weight(key: &[u8], min_weight_ratio: f64) -> Result<(), QkdError> {
+        let set_bits: u32 = key.iter().map(|byte| byte.count_ones()).sum();
+        Self::check_bit_weight(set_bits as usize, key.len() * 8, min_weight_ratio)
+    }
+
+    /// Applies the Hamming-weight check to a key of `total_bits` bits, `set_bits` of them set.
+    fn check_bit_weight(set_bits: usize, total_bits: usize, min_weight_ratio: f64) -> Result<(), QkdError> {
+        if total_bits == 0 {
+            return Err(QkdError::WeakKey);
+        }
+
+        let ratio = set_bits as f64 / total_bits as f64;
+        if ratio < min_weight_ratio || ratio > 1.0 - min_weight_ratio {
+            return Err(QkdError::WeakKey);
+        }
+        Ok(())
+    }
+
+    /// Derives the key shared by both QKD parties from their combined random contributions.
+    ///
+    /// The key is expanded with HKDF-SHA256 from both contributions in full, in order,
+    /// and is deterministic for a given pair of contributions, so both parties obtain
+    /// identical key material.
+    ///
+    /// # Arguments
+    /// * `contribution_1` - The first party's random contribution.
+    /// * `contribution_2` - The second party's random contribution.
+    ///
+    /// # Returns
+    /// * `Vec<u8>` containing the `QKD_KEY_LENGTH`-byte shared key.
+    pub fn generate_shared_key(contribution_1: u64, contribution_2: u64) -> Vec<u8> {
+        let mut input = [0u8; 16];
+        input[..8].copy_from_slice(&contribution_1.to_be_bytes());
+        input[8..].copy_from_slice(&contribution_2.to_be_bytes());
+
+        let mut key = vec![0u8; QKD_KEY_LENGTH];
+        Hkdf::<Sha256>::new(None, &input)
+            .expand(SHARED_KEY_KDF_INFO, &mut key)
+            .expect("QKD_KEY_LENGTH is a valid HKDF-SHA256 output length");
+        key
+    }
+
+    /// Generates a raw 16-byte QKD key, including simulated measurement errors.
+    ///
+    /// Callers are responsible for checking that the parties are entangled.
+    ///
+    /// # Arguments
+    /// * `rng` - The source of randomness for the key and measurement errors.
+    ///
+    /// # Returns
+    /// * `Vec<u8>` containing the raw quantum key.
+    pub fn generate_raw_key<R: Rng>(rng: &mut R) -> Vec<u8> {
         let mut key: Vec<u8> = (0..16).map(|_| rng.gen_range(0..=255)).collect(); // Generate a 16-byte quantum key
 
         // Simulate measurement errors (in real QKD, errors occur due to quantum noise)
@@ -40,10 +277,163 @@ impl QuantumCryptography {
             }
         });
 
-        Ok(key)
+        key
+    }
+
+    /// Encrypts a message with a true one-time pad.
+    ///
+    /// Each key byte is used at most once, so the key must be at least as long as the message.
+    ///
+    /// # Arguments
+    /// * `message` - The plaintext message as a `&str`.
+    /// * `key` - The quantum key.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` containing the encrypted ciphertext.
+    /// * `Err(CryptoError::KeyTooShort)` if the key is shorter than the message.
+    pub fn encrypt_otp(message: &str, key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if key.len() < message.len() {
+            return Err(CryptoError::KeyTooShort {
+                needed: message.len(),
+                got: key.len(),
+            });
+        }
+
+        Ok(message
+            .bytes()
+            .zip(key.iter())
+            .map(|(m_byte, k_byte)| m_byte ^ k_byte) // XOR for encryption
+            .collect())
+    }
+
+    /// Decrypts a message encrypted with `encrypt_otp`.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - The encrypted message.
+    /// * `key` - The quantum key used for encryption.
+    ///
+    /// # Returns
+    /// * `Ok(String)` containing the decrypted message.
+    /// * `Err(CryptoError::KeyTooShort)` if the key is shorter than the ciphertext.
+    /// * `Err(CryptoError::InvalidPlaintext)` if the result is not valid UTF-8.
+    pub fn decrypt_otp(ciphertext: &[u8], key: &[u8]) -> Result<String, CryptoError> {
+        if key.len() < ciphertext.len() {
+            return Err(CryptoError::KeyTooShort {
+                needed: ciphertext.len(),
+                got: key.len(),
+            });
+        }
+
+        let decrypted_bytes: Vec<u8> = ciphertext
+            .iter()
+            .zip(key.iter())
+            .map(|(c_byte, k_byte)| c_byte ^ k_byte) // XOR for decryption
+            .collect();
+
+        String::from_utf8(decrypted_bytes).map_err(|_| CryptoError::InvalidPlaintext)
+    }
+
+    /// Derives a 256-bit AES key from a QKD key with HKDF-SHA256.
+    ///
+    /// # Arguments
+    /// * `qkd_key` - The shared quantum key.
+    ///
+    /// # Returns
+    /// * `[u8; 32]` - The derived AES-256 key.
+    pub fn derive_aes_key(qkd_key: &[u8]) -> [u8; 32] {
+        let mut aes_key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, qkd_key)
+            .expand(AES_GCM_KDF_INFO, &mut aes_key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        aes_key
+    }
+
+    /// Derives a 256-bit packet MAC key from a QKD key with HKDF-SHA256.
+    ///
+    /// The MAC key is independent of the key bytes used for encryption, so authenticating
+    /// a packet never exposes one-time pad material.
+    ///
+    /// # Arguments
+    /// * `qkd_key` - The shared quantum key.
+    ///
+    /// # Returns
+    /// * `[u8; 32]` - The derived HMAC-SHA256 key.
+    pub fn derive_mac_key(qkd_key: &[u8]) -> [u8; 32] {
+        let mut mac_key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, qkd_key)
+            .expand(MAC_KDF_INFO, &mut mac_key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        mac_key
+    }
+
+    /// Encrypts a message with AES-256-GCM under a key derived from the QKD key.
+    ///
+    /// A fresh random nonce is drawn from the thread-local generator per message and
+    /// prepended to the ciphertext; see `encrypt_aes_gcm_with_rng`.
+    ///
+    /// # Arguments
+    /// * `message` - The plaintext message as a `&str`.
+    /// * `key` - The quantum key.
+    ///
+    /// # Returns
+    /// * `Vec<u8>` containing the nonce followed by the ciphertext and authentication tag.
+    pub fn encrypt_aes_gcm(message: &str, key: &[u8]) -> Vec<u8> {
+        Self::encrypt_aes_gcm_with_rng(message, key, &mut rand::thread_rng())
+    }
+
+    /// Encrypts a message with AES-256-GCM, drawing the nonce from the given generator.
+    ///
+    /// A seeded generator makes the ciphertext reproducible, e.g. in seeded simulations.
+    /// The generator must never repeat a nonce under the same key.
+    ///
+    /// # Arguments
+    /// * `message` - The plaintext message as a `&str`.
+    /// * `key` - The quantum key.
+    /// * `rng` - The source of the nonce.
+    ///
+    /// # Returns
+    /// * `Vec<u8>` containing the nonce followed by the ciphertext and authentication tag.
+    pub fn encrypt_aes_gcm_with_rng<R: RngCore>(message: &str, key: &[u8], rng: &mut R) -> Vec<u8> {
+        let aes_key = Self::derive_aes_key(key);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&aes_key));
+        let mut nonce = [0u8; AES_GCM_NONCE_LENGTH];
+        rng.fill_bytes(&mut nonce);
+
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), message.as_bytes())
+            .expect("AES-GCM encryption of an in-memory message cannot fail");
+        [nonce.as_slice(), &ciphertext].concat()
+    }
+
+    /// Decrypts and authenticates a message encrypted with `encrypt_aes_gcm`.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - The nonce followed by the ciphertext and authentication tag.
+    /// * `key` - The quantum key used for encryption.
+    ///
+    /// # Returns
+    /// * `Ok(String)` containing the decrypted message.
+    /// * `Err(CryptoError::AuthenticationFailed)` if the ciphertext was modified or truncated.
+    /// * `Err(CryptoError::InvalidPlaintext)` if the result is not valid UTF-8.
+    pub fn decrypt_aes_gcm(ciphertext: &[u8], key: &[u8]) -> Result<String, CryptoError> {
+        if ciphertext.len() < AES_GCM_NONCE_LENGTH {
+            return Err(CryptoError::AuthenticationFailed);
+        }
+        let (nonce, body) = ciphertext.split_at(AES_GCM_NONCE_LENGTH);
+
+        let aes_key = Self::derive_aes_key(key);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&aes_key));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), body)
+            .map_err(|_| CryptoError::AuthenticationFailed)?;
+        String::from_utf8(plaintext).map_err(|_| CryptoError::InvalidPlaintext)
     }
 
-    /// Encrypts a message using a quantum-secure one-time pad.
+    /// Encrypts a message by XORing it with the key repeated cyclically.
+    ///
+    /// This is NOT a one-time pad: once the message is longer than the key, key bytes
+    /// are reused and the result is a repeating-key XOR cipher that is trivially
+    /// breakable. Prefer `encrypt_otp` whenever enough key material is available.
     ///
     /// # Arguments
     /// * `message` - The plaintext message as a `&str`.
@@ -51,7 +441,7 @@ impl QuantumCryptography {
     ///
     /// # Returns
     /// * `Vec<u8>` containing the encrypted ciphertext.
-    pub fn encrypt(message: &str, key: &Vec<u8>) -> Vec<u8> {
+    pub fn encrypt_stream(message: &str, key: &Vec<u8>) -> Vec<u8> {
         message
             .bytes()
             .zip(key.iter().cycle()) // Use the key cyclically
@@ -59,7 +449,24 @@ impl QuantumCryptography {
             .collect()
     }
 
-    /// Decrypts a quantum-encrypted message.
+    /// Encrypts raw bytes with a quantum key (XOR, so the same call also decrypts).
+    ///
+    /// # Arguments
+    /// * `data` - The bytes to encrypt or decrypt.
+    /// * `key` - The quantum key as a `Vec<u8>`.
+    ///
+    /// # Returns
+    /// * `Vec<u8>` containing the transformed bytes.
+    pub fn encrypt_bytes(data: &[u8], key: &Vec<u8>) -> Vec<u8> {
+        data.iter()
+            .zip(key.iter().cycle()) // Use the key cyclically
+            .map(|(d_byte, k_byte)| d_byte ^ k_byte)
+            .collect()
+    }
+
+    /// Decrypts a message encrypted with `encrypt_stream`.
+    ///
+    /// Shares the key-reuse weakness of `encrypt_stream`.
     ///
     /// # Arguments
     /// * `ciphertext` - The encrypted message as a `Vec<u8>`.
@@ -67,7 +474,7 @@ impl QuantumCryptography {
     ///
     /// # Returns
     /// * `String` containing the decrypted message.
-    pub fn decrypt(ciphertext: &Vec<u8>, key: &Vec<u8>) -> String {
+    pub fn decrypt_stream(ciphertext: &Vec<u8>, key: &Vec<u8>) -> String {
         let decrypted_bytes: Vec<u8> = ciphertext
             .iter()
             .zip(key.iter().cycle()) // Use the key cyclically
@@ -76,4 +483,708 @@ impl QuantumCryptography {
 
         String::from_utf8(decrypted_bytes).unwrap_or_else(|_| "Decryption failed".to_string())
     }
+
+    /// Encrypts a stream chunk by chunk without loading it fully into memory.
+    ///
+    /// Each chunk of `STREAM_CHUNK_SIZE` bytes is encrypted with ChaCha20 under a key
+    /// derived from the quantum key with HKDF-SHA256. Every chunk uses its own nonce,
+    /// derived from the chunk counter, so no keystream bytes repeat across chunks.
+    ///
+    /// # Arguments
+    /// * `reader` - The plaintext source.
+    /// * `writer` - The ciphertext destination.
+    /// * `key` - The quantum key (must not be empty).
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the number of bytes processed.
+    /// * `Err(io::Error)` if the key is empty or an I/O operation fails.
+    pub fn stream_encrypt<R: Read, W: Write>(reader: &mut R, writer: &mut W, key: &[u8]) -> io::Result<u64> {
+        if key.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Stream cipher key must not be empty."));
+        }
+
+        let hkdf = Hkdf::<Sha256>::new(None, key);
+        let mut cipher_key = [0u8; 32];
+        hkdf.expand(STREAM_KDF_INFO, &mut cipher_key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+
+        let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+        let mut counter: u64 = 0;
+        let mut total: u64 = 0;
+
+        loop {
+            let read = Self::read_chunk(reader, &mut buffer)?;
+            if read == 0 {
+                break;
+            }
+
+            let nonce = Self::chunk_nonce(&hkdf, counter);
+            ChaCha20::new(&cipher_key.into(), &nonce.into()).apply_keystream(&mut buffer[..read]);
+            writer.write_all(&buffer[..read])?;
+
+            counter += 1;
+            total += read as u64;
+        }
+
+        writer.flush()?;
+        Ok(total)
+    }
+
+    /// Decrypts a stream produced by `stream_encrypt` with the same key.
+    ///
+    /// # Arguments
+    /// * `reader` - The ciphertext source.
+    /// * `writer` - The plaintext destination.
+    /// * `key` - The quantum key used for encryption.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` with the number of bytes processed.
+    /// * `Err(io::Error)` if the key is empty or an I/O operation fails.
+    pub fn stream_decrypt<R: Read, W: Write>(reader: &mut R, writer: &mut W, key: &[u8]) -> io::Result<u64> {
+        // Applying the same keystream again restores the plaintext
+        Self::stream_encrypt(reader, writer, key)
+    }
+
+    /// Fills `buffer` as far as possible, returning fewer bytes only at end of stream.
+    fn read_chunk<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match reader.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(filled)
+    }
+
+    /// Derives the ChaCha20 nonce for a single chunk from the stream's HKDF state and chunk counter.
+    fn chunk_nonce(hkdf: &Hkdf<Sha256>, counter: u64) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        hkdf.expand_multi_info(&[STREAM_NONCE_KDF_INFO, &counter.to_be_bytes()], &mut nonce)
+            .expect("12 bytes is a valid HKDF-SHA256 output length");
+        nonce
+    }
+
+    /// Compares two keys bit by bit.
+    ///
+    /// If the keys have different lengths, the shorter one is treated as zero-padded.
+    ///
+    /// # Arguments
+    /// * `a` - The first key.
+    /// * `b` - The second key.
+    ///
+    /// # Returns
+    /// * `KeyDiff` with the differing bit positions and the Hamming distance.
+    pub fn key_diff(a: &[u8], b: &[u8]) -> KeyDiff {
+        let len = a.len().max(b.len());
+        let mut differing_bits = Vec::new();
+
+        for i in 0..len {
+            let diff = a.get(i).copied().unwrap_or(0) ^ b.get(i).copied().unwrap_or(0);
+            for bit in 0..8 {
+                if diff & (0x80 >> bit) != 0 {
+                    differing_bits.push(i * 8 + bit);
+                }
+            }
+        }
+
+        KeyDiff {
+            hamming_distance: differing_bits.len(),
+            differing_bits,
+        }
+    }
+
+    /// Runs the BB84 protocol between two entangled nodes.
+    ///
+    /// # Arguments
+    /// * `network` - The mutable reference to the quantum network.
+    /// * `node_id_1` - The ID of the sending node (Alice).
+    /// * `node_id_2` - The ID of the receiving node (Bob).
+    /// * `n_bits` - Number of qubits Alice transmits.
+    /// * `error_rate` - Probability that the channel flips a transmitted bit.
+    /// * `qber_threshold` - QBER above which the exchange is aborted.
+    ///
+    /// # Returns
+    /// * `Ok(Bb84Result)` with the sifted keys and estimated QBER.
+    /// * `Err(QkdError::EavesdropperDetected)` if the QBER exceeds `qber_threshold`.
+    /// * `Err(QkdError::WeakKey)` if the sifted key fails the Hamming-weight check.
+    /// * `Err(QkdError)` if the nodes cannot perform QKD, as for `quantum_key_distribution`.
+    pub fn bb84_exchange(
+        network: &mut QuantumNetwork,
+        node_id_1: u32,
+        node_id_2: u32,
+        n_bits: usize,
+        error_rate: f64,
+        qber_threshold: f64,
+    ) -> Result<Bb84Result, QkdError> {
+        Self::check_qkd_pair(network, node_id_1, node_id_2)?;
+
+        let result = Self::bb84_sift(network.rng(), n_bits, error_rate, qber_threshold)?;
+        let set_bits: u32 = result.key.iter().map(|byte| byte.count_ones()).sum(); // Padding bits are zero
+        Self::check_bit_weight(set_bits as usize, result.key_bits, MIN_KEY_WEIGHT_RATIO)?;
+        network.touch_link(node_id_1, node_id_2);
+        Ok(result)
+    }
+
+    /// Simulates BB84 transmission, sifting and QBER estimation.
+    ///
+    /// Alice sends random bits in random bases, Bob measures in random bases, and
+    /// only positions where the bases match are kept. A sample of the sifted bits is
+    /// disclosed to estimate the QBER and then discarded.
+    ///
+    /// # Arguments
+    /// * `rng` - The source of randomness for bits, bases, channel errors and sampling.
+    /// * `n_bits` - Number of qubits Alice transmits.
+    /// * `error_rate` - Probability that the channel flips a transmitted bit.
+    /// * `qber_threshold` - QBER above which the exchange is aborted.
+    ///
+    /// # Returns
+    /// * `Ok(Bb84Result)` with the sifted keys and estimated QBER.
+    /// * `Err(QkdError)` if an eavesdropper is suspected or too few bits remain.
+    pub fn bb84_sift<R: Rng>(rng: &mut R, n_bits: usize, error_rate: f64, qber_threshold: f64) -> Result<Bb84Result, QkdError> {
+        let mut alice_sifted = Vec::new();
+        let mut bob_sifted = Vec::new();
+
+        for _ in 0..n_bits {
+            let alice_bit: u8 = rng.gen_range(0..=1);
+            let alice_basis: bool = rng.gen();
+            let bob_basis: bool = rng.gen();
+
+            if alice_basis != bob_basis {
+                continue; // Mismatched bases yield uncorrelated results and are sifted out
+            }
+
+            let flipped = rng.gen::<f64>() < error_rate;
+            alice_sifted.push(alice_bit);
+            bob_sifted.push(if flipped { alice_bit ^ 1 } else { alice_bit });
+        }
+        let sifted_bits = alice_sifted.len();
+
+        // Disclose a random sample to estimate the QBER, then drop it from the key
+        let sample_size = (sifted_bits as f64 * BB84_SAMPLE_FRACTION).ceil() as usize;
+        let mut positions: Vec<usize> = (0..sifted_bits).collect();
+        positions.shuffle(rng);
+        let sampled: Vec<usize> = positions[..sample_size.min(sifted_bits)].to_vec();
+
+        let errors = sampled.iter().filter(|&&i| alice_sifted[i] != bob_sifted[i]).count();
+        let qber = if sampled.is_empty() { 0.0 } else { errors as f64 / sampled.len() as f64 };
+        if qber > qber_threshold {
+            return Err(QkdError::EavesdropperDetected);
+        }
+
+        let mut is_sampled = vec![false; sifted_bits];
+        sampled.iter().for_each(|&i| is_sampled[i] = true);
+        let keep = |bits: &[u8]| -> Vec<u8> {
+            bits.iter().zip(&is_sampled).filter(|(_, &s)| !s).map(|(&b, _)| b).collect()
+        };
+        let (alice_key, bob_key) = (keep(&alice_sifted), keep(&bob_sifted));
+        if alice_key.is_empty() {
+            return Err(QkdError::InsufficientKeyMaterial);
+        }
+
+        Ok(Bb84Result {
+            key: Self::pack_bits(&alice_key),
+            peer_key: Self::pack_bits(&bob_key),
+            key_bits: alice_key.len(),
+            sifted_bits,
+            qber,
+        })
+    }
+
+    /// Runs the E91 (Ekert) protocol over the entanglement between two nodes.
+    ///
+    /// Each pair is measured by both parties along randomly chosen polarizer angles.
+    /// Matched-angle rounds form the key; the CHSH value is estimated from the rounds
+    /// using Alice's outer and Bob's outer angles. Pairs prepared in a Bell state other
+    /// than Φ+ are first rotated to Φ+ by a local correction on Bob's side. A plain link
+    /// only marks the second node's state as entangled, so the pair counts as intact while
+    /// either node's state is entangled; once both have decohered into local states, the
+    /// measurements are uncorrelated and the CHSH value stays within the classical bound of 2.
+    ///
+    /// # Arguments
+    /// * `network` - The mutable reference to the quantum network.
+    /// * `node_id_1` - The ID of the first node (Alice).
+    /// * `node_id_2` - The ID of the second node (Bob).
+    /// * `n_pairs` - Number of entangled pairs consumed.
+    ///
+    /// # Returns
+    /// * `Ok(E91Result)` with the keys and the CHSH value.
+    /// * `Err(QkdError::InsufficientKeyMaterial)` if no matched-angle rounds occurred.
+    /// * `Err(QkdError)` if the nodes cannot perform QKD (see `check_qkd_pair`), including
+    ///   when the pair is older than the network's maximum entanglement age.
+    pub fn e91_exchange(network: &mut QuantumNetwork, node_id_1: u32, node_id_2: u32, n_pairs: usize) -> Result<E91Result, QkdError> {
+        Self::check_qkd_pair(network, node_id_1, node_id_2)?;
+
+        let node_1 = network.get_node(node_id_1).ok_or(QkdError::NodeNotFound(node_id_1))?;
+        let node_2 = network.get_node(node_id_2).ok_or(QkdError::NodeNotFound(node_id_2))?;
+        let is_entangled_state = |state: &QuantumState| {
+            matches!(state, QuantumState::Entangled(_) | QuantumState::GroupEntangled(_) | QuantumState::Bell(_))
+        };
+        let genuine = is_entangled_state(&node_1.state) || is_entangled_state(&node_2.state);
+
+        let rng = network.rng();
+        let mut alice_bits = Vec::new();
+        let mut bob_bits = Vec::new();
+        let mut products = [[0.0f64; 3]; 3]; // Sum of outcome products per angle pair
+        let mut counts = [[0usize; 3]; 3];   // Rounds per angle pair
+
+        for _ in 0..n_pairs {
+            let (i, j) = (rng.gen_range(0..3), rng.gen_range(0..3));
+            let alice: i8 = if rng.gen() { 1 } else { -1 };
+            let bob = if genuine {
+                // Φ+ correlation: outcomes agree with probability cos²(α - β)
+                let correlation = (2.0 * (E91_ALICE_ANGLES[i] - E91_BOB_ANGLES[j])).cos();
+                if rng.gen::<f64>() < (1.0 + correlation) / 2.0 { alice } else { -alice }
+            } else if rng.gen() {
+                1
+            } else {
+                -1
+            };
+
+            products[i][j] += f64::from(alice * bob);
+            counts[i][j] += 1;
+            if (i, j) == (1, 0) || (i, j) == (2, 1) {
+                alice_bits.push(u8::from(alice < 0)); // Matched angles π/8 and π/4
+                bob_bits.push(u8::from(bob < 0));
+            }
+        }
+
+        if alice_bits.is_empty() {
+            return Err(QkdError::InsufficientKeyMaterial);
+        }
+
+        let expectation = |i: usize, j: usize| {
+            if counts[i][j] == 0 { 0.0 } else { products[i][j] / counts[i][j] as f64 }
+        };
+        let chsh = (expectation(0, 0) - expectation(0, 2) + expectation(2, 0) + expectation(2, 2)).abs();
+
+        network.touch_link(node_id_1, node_id_2);
+        Ok(E91Result {
+            key: Self::pack_bits(&alice_bits),
+            peer_key: Self::pack_bits(&bob_bits),
+            key_bits: alice_bits.len(),
+            chsh,
+        })
+    }
+
+    /// Reconciles two sifted keys with a simplified Cascade protocol.
+    ///
+    /// Each pass shuffles bit positions with a public permutation, splits them into
+    /// blocks and compares block parities; in every mismatched block a binary search
+    /// over parities locates one error, which Bob corrects. Passes repeat until several
+    /// in a row find no mismatch. Only the common prefix of the keys is reconciled.
+    ///
+    /// # Arguments
+    /// * `alice_key` - Alice's key, left unchanged.
+    /// * `bob_key` - Bob's key, corrected towards Alice's.
+    ///
+    /// # Returns
+    /// * `usize` - The number of parity bits disclosed over the public channel.
+    pub fn reconcile(alice_key: &mut Vec<u8>, bob_key: &mut Vec<u8>) -> usize {
+        let n = alice_key.len().min(bob_key.len()) * 8;
+        let bit = |key: &[u8], i: usize| (key[i / 8] >> (7 - i % 8)) & 1;
+        let parity = |key: &[u8], positions: &[usize]| positions.iter().fold(0, |acc, &i| acc ^ bit(key, i));
+
+        let mut disclosed = 0;
+        let mut clean_passes = 0;
+        for pass in 0..CASCADE_MAX_PASSES {
+            if clean_passes == CASCADE_CLEAN_PASSES || n == 0 {
+                break;
+            }
+
+            let mut positions: Vec<usize> = (0..n).collect();
+            positions.shuffle(&mut StdRng::seed_from_u64(pass as u64));
+
+            let mut corrected = false;
+            for block in positions.chunks(CASCADE_BLOCK_BITS) {
+                disclosed += 1;
+                if parity(alice_key, block) == parity(bob_key, block) {
+                    continue;
+                }
+
+                // Binary search for the odd error by comparing parities of halves
+                let mut candidates = block;
+                while candidates.len() > 1 {
+                    let (first, second) = candidates.split_at(candidates.len() / 2);
+                    disclosed += 1;
+                    candidates = if parity(alice_key, first) != parity(bob_key, first) { first } else { second };
+                }
+                let i = candidates[0];
+                bob_key[i / 8] ^= 0x80 >> (i % 8);
+                corrected = true;
+            }
+
+            clean_passes = if corrected { 0 } else { clean_passes + 1 };
+        }
+
+        disclosed
+    }
+
+    /// Compresses a reconciled key to remove an eavesdropper's partial information.
+    ///
+    /// Uses the public `DEFAULT_AMPLIFICATION_SEED`; see `privacy_amplify_with_seed`.
+    ///
+    /// # Arguments
+    /// * `raw_key` - The reconciled key shared by both parties.
+    /// * `output_len` - The desired key length in bytes.
+    /// * `qber` - The estimated quantum bit error rate.
+    ///
+    /// # Returns
+    /// * `Vec<u8>` - The amplified key, at most `output_len` bytes.
+    pub fn privacy_amplify(raw_key: &[u8], output_len: usize, qber: f64) -> Vec<u8> {
+        Self::privacy_amplify_with_seed(raw_key, output_len, qber, DEFAULT_AMPLIFICATION_SEED)
+    }
+
+    /// Compresses a reconciled key with a Toeplitz-matrix universal hash.
+    ///
+    /// The output is capped at the secure length n·(1 - 2·h(qber)) bits, where h is the
+    /// binary entropy, so higher error rates yield shorter keys. The seed defines the
+    /// public Toeplitz matrix; both parties must use the same seed.
+    ///
+    /// # Arguments
+    /// * `raw_key` - The reconciled key shared by both parties.
+    /// * `output_len` - The desired key length in bytes.
+    /// * `qber` - The estimated quantum bit error rate.
+    /// * `seed` - Seed of the Toeplitz matrix.
+    ///
+    /// # Returns
+    /// * `Vec<u8>` - The amplified key, at most `output_len` bytes (empty if nothing is secure).
+    pub fn privacy_amplify_with_seed(raw_key: &[u8], output_len: usize, qber: f64, seed: u64) -> Vec<u8> {
+        let n = raw_key.len() * 8;
+        let secure_fraction = if qber < 0.5 {
+            (1.0 - 2.0 * Self::binary_entropy(qber)).max(0.0)
+        } else {
+            0.0 // At 50% errors or more nothing is secret
+        };
+        let secure_bytes = (n as f64 * secure_fraction / 8.0).floor() as usize;
+        let m = output_len.min(secure_bytes) * 8;
+        if m == 0 {
+            return Vec::new();
+        }
+
+        // A Toeplitz matrix is constant along diagonals, so m + n - 1 bits define it
+        let mut rng = StdRng::seed_from_u64(seed);
+        let diagonals: Vec<u8> = (0..m + n - 1).map(|_| rng.gen_range(0..=1)).collect();
+        let bit = |i: usize| (raw_key[i / 8] >> (7 - i % 8)) & 1;
+
+        let output: Vec<u8> = (0..m)
+            .map(|row| (0..n).fold(0, |acc, col| acc ^ (diagonals[row + n - 1 - col] & bit(col))))
+            .collect();
+        Self::pack_bits(&output)
+    }
+
+    /// Computes the binary entropy h(p) in bits; 0 outside (0, 1).
+    fn binary_entropy(p: f64) -> f64 {
+        if p <= 0.0 || p >= 1.0 {
+            return 0.0;
+        }
+        -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+    }
+
+    /// Packs a slice of 0/1 values into bytes, MSB-first.
+    fn pack_bits(bits: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0u8; (bits.len() + 7) / 8];
+        for (i, &bit) in bits.iter().enumerate() {
+            if bit != 0 {
+                bytes[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Builds a seeded network with two entangled nodes, 1 and 2.
+    fn entangled_pair() -> QuantumNetwork {
+        let mut network = QuantumNetwork::new();
+        network.set_seed(7);
+        network.add_node(1, (0.0, 0.0), QuantumState::Zero);
+        network.add_node(2, (1.0, 0.0), QuantumState::Zero);
+        network.entangle_nodes(1, 2).unwrap();
+        network
+    }
+
+    #[test]
+    fn qkd_stores_the_same_key_in_both_nodes() {
+        let mut network = entangled_pair();
+
+        let key = QuantumCryptography::quantum_key_distribution(&mut network, 1, 2).unwrap();
+        assert_eq!(key.len(), QKD_KEY_LENGTH);
+        assert_eq!(network.get_node(1).unwrap().key_store[&2], key);
+        assert_eq!(network.get_node(2).unwrap().key_store[&1], key);
+    }
+
+    #[test]
+    fn qkd_key_lets_the_peer_decrypt_messages() {
+        let mut network = entangled_pair();
+        QuantumCryptography::quantum_key_distribution(&mut network, 1, 2).unwrap();
+
+        let packet = network.get_node_mut(1).unwrap().send_packet(2, "hello").unwrap();
+        let message = network.get_node_mut(2).unwrap().receive_packet(&packet);
+        assert_eq!(message.as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn generate_shared_key_is_deterministic() {
+        assert_eq!(
+            QuantumCryptography::generate_shared_key(1, 2),
+            QuantumCryptography::generate_shared_key(1, 2)
+        );
+        assert_ne!(
+            QuantumCryptography::generate_shared_key(1, 2),
+            QuantumCryptography::generate_shared_key(2, 1)
+        );
+    }
+
+    #[test]
+    fn generate_shared_key_does_not_collapse_contributions() {
+        // Pairs that agree on any XOR/rotation combination of the two words still differ
+        let (a, b) = (0x0123_4567_89ab_cdef_u64, 0xfedc_ba98_7654_3210_u64);
+        assert_ne!(
+            QuantumCryptography::generate_shared_key(a, b),
+            QuantumCryptography::generate_shared_key(a ^ b.rotate_left(32), 0)
+        );
+    }
+
+    #[test]
+    fn e91_violates_chsh_only_on_an_intact_pair() {
+        let mut network = entangled_pair();
+        let result = QuantumCryptography::e91_exchange(&mut network, 1, 2, 4000).unwrap();
+        assert!(result.chsh > 2.0, "intact pair gave CHSH {}", result.chsh);
+
+        // The link survives, but the nodes' states have decohered into plain basis states
+        let mut network = entangled_pair();
+        network.get_node_mut(1).unwrap().state = QuantumState::Zero;
+        network.get_node_mut(2).unwrap().state = QuantumState::One;
+        let result = QuantumCryptography::e91_exchange(&mut network, 1, 2, 4000).unwrap();
+        assert!(result.chsh <= 2.0, "broken pair gave CHSH {}", result.chsh);
+    }
+
+    #[test]
+    fn e91_runs_the_qkd_pair_checks() {
+        let mut network = entangled_pair();
+        assert_eq!(QuantumCryptography::e91_exchange(&mut network, 1, 1, 100).unwrap_err(), QkdError::SelfPair);
+        assert_eq!(QuantumCryptography::e91_exchange(&mut network, 1, 9, 100).unwrap_err(), QkdError::NodeNotFound(9));
+
+        network.add_node(3, (2.0, 0.0), QuantumState::Zero);
+        assert_eq!(QuantumCryptography::e91_exchange(&mut network, 1, 3, 100).unwrap_err(), QkdError::NotEntangled);
+
+        network.set_max_entanglement_age(Some(Duration::ZERO));
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(QuantumCryptography::e91_exchange(&mut network, 1, 2, 100).unwrap_err(), QkdError::StaleEntanglement);
+    }
+
+
+    /// Encrypts `plaintext` with the streaming cipher and returns the ciphertext.
+    fn stream_ciphertext(plaintext: &[u8], key: &[u8]) -> Vec<u8> {
+        let mut ciphertext = Vec::new();
+        let written = QuantumCryptography::stream_encrypt(&mut &plaintext[..], &mut ciphertext, key).unwrap();
+        assert_eq!(written, plaintext.len() as u64);
+        ciphertext
+    }
+
+    #[test]
+    fn stream_round_trips_a_multi_megabyte_payload() {
+        let key = QuantumCryptography::generate_shared_key(3, 4);
+        let plaintext: Vec<u8> = (0..3 * 1024 * 1024 + 17).map(|i| (i * 31 % 251) as u8).collect();
+
+        let ciphertext = stream_ciphertext(&plaintext, &key);
+        assert_ne!(ciphertext, plaintext);
+
+        let mut decrypted = Vec::new();
+        QuantumCryptography::stream_decrypt(&mut &ciphertext[..], &mut decrypted, &key).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn stream_chunks_use_distinct_keystreams() {
+        let key = QuantumCryptography::generate_shared_key(3, 4);
+        let ciphertext = stream_ciphertext(&vec![0u8; 2 * STREAM_CHUNK_SIZE], &key);
+
+        assert_ne!(ciphertext[..STREAM_CHUNK_SIZE], ciphertext[STREAM_CHUNK_SIZE..]);
+    }
+
+    #[test]
+    fn tampered_stream_chunk_changes_the_output() {
+        let key = QuantumCryptography::generate_shared_key(3, 4);
+        let plaintext = vec![0x5au8; 3 * STREAM_CHUNK_SIZE];
+        let mut ciphertext = stream_ciphertext(&plaintext, &key);
+        let tampered = STREAM_CHUNK_SIZE + 100;
+        ciphertext[tampered] ^= 0x01;
+
+        let mut decrypted = Vec::new();
+        QuantumCryptography::stream_decrypt(&mut &ciphertext[..], &mut decrypted, &key).unwrap();
+        assert_ne!(decrypted, plaintext);
+        let changed: Vec<usize> = (0..plaintext.len()).filter(|&i| decrypted[i] != plaintext[i]).collect();
+        assert_eq!(changed, vec![tampered]); // The unauthenticated stream cipher flips exactly that bit
+        assert_eq!(decrypted[tampered], 0x5a ^ 0x01);
+    }
+
+
+    #[test]
+    fn qkd_rejects_a_self_pair() {
+        let mut network = entangled_pair();
+
+        assert_eq!(QuantumCryptography::quantum_key_distribution(&mut network, 1, 1), Err(QkdError::SelfPair));
+        assert!(network.get_node(1).unwrap().key_store.is_empty());
+    }
+
+
+    #[test]
+    fn qkd_rejects_a_stale_pair_until_it_is_regenerated() {
+        let mut network = entangled_pair();
+        network.set_max_entanglement_age(Some(Duration::from_millis(5)));
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(
+            QuantumCryptography::quantum_key_distribution(&mut network, 1, 2),
+            Err(QkdError::StaleEntanglement)
+        );
+        assert!(network.get_node(1).unwrap().key_store.is_empty());
+
+        network.entangle_nodes(1, 2).unwrap(); // Fresh entanglement replaces the stale pair
+        assert!(QuantumCryptography::quantum_key_distribution(&mut network, 1, 2).is_ok());
+    }
+
+
+    #[test]
+    fn key_diff_reports_known_positions() {
+        let a = [0b1010_0000, 0xff, 0x00];
+        let b = [0b0010_0001, 0xff, 0x10];
+
+        assert_eq!(
+            QuantumCryptography::key_diff(&a, &b),
+            KeyDiff {
+                differing_bits: vec![0, 7, 19],
+                hamming_distance: 3,
+            }
+        );
+        assert_eq!(QuantumCryptography::key_diff(&a, &a).hamming_distance, 0);
+    }
+
+    #[test]
+    fn key_diff_zero_pads_the_shorter_key() {
+        let diff = QuantumCryptography::key_diff(&[0x00], &[0x00, 0x81]);
+
+        assert_eq!(diff.differing_bits, vec![8, 15]);
+        assert_eq!(diff.hamming_distance, 2);
+    }
+
+
+    #[test]
+    fn otp_round_trips_with_a_long_enough_key() {
+        let key = QuantumCryptography::generate_shared_key(5, 6);
+
+        let ciphertext = QuantumCryptography::encrypt_otp("one-time pad", &key).unwrap();
+        assert_eq!(ciphertext.len(), "one-time pad".len());
+        assert_ne!(ciphertext, b"one-time pad");
+        assert_eq!(QuantumCryptography::decrypt_otp(&ciphertext, &key).unwrap(), "one-time pad");
+    }
+
+    #[test]
+    fn otp_rejects_keys_shorter_than_the_message() {
+        let key = QuantumCryptography::generate_shared_key(5, 6);
+        let message = "a message longer than sixteen bytes";
+
+        assert_eq!(
+            QuantumCryptography::encrypt_otp(message, &key),
+            Err(CryptoError::KeyTooShort {
+                needed: message.len(),
+                got: QKD_KEY_LENGTH,
+            })
+        );
+        assert_eq!(
+            QuantumCryptography::decrypt_otp(&[0u8; 20], &key),
+            Err(CryptoError::KeyTooShort { needed: 20, got: QKD_KEY_LENGTH })
+        );
+    }
+
+    #[test]
+    fn privacy_amplification_shrinks_with_qber_and_is_reproducible() {
+        let raw_key: Vec<u8> = (0..64u8).map(|i| i.wrapping_mul(37).wrapping_add(11)).collect();
+
+        let lengths: Vec<usize> = [0.0, 0.05, 0.1, 0.2]
+            .iter()
+            .map(|&qber| QuantumCryptography::privacy_amplify(&raw_key, 64, qber).len())
+            .collect();
+        assert_eq!(lengths, vec![64, 27, 3, 0]); // 512·(1 - 2·h(qber)) bits, rounded down to bytes
+
+        let first = QuantumCryptography::privacy_amplify_with_seed(&raw_key, 16, 0.01, 99);
+        assert_eq!(first.len(), 16);
+        assert_eq!(QuantumCryptography::privacy_amplify_with_seed(&raw_key, 16, 0.01, 99), first);
+        assert_ne!(QuantumCryptography::privacy_amplify_with_seed(&raw_key, 16, 0.01, 100), first);
+    }
+
+    #[test]
+    fn reconcile_corrects_every_bit_error() {
+        let alice: Vec<u8> = (0..32u8).map(|i| i.wrapping_mul(73).wrapping_add(5)).collect();
+        let mut bob = alice.clone();
+        for bit in [3, 40, 41, 150, 255] {
+            bob[bit / 8] ^= 0x80 >> (bit % 8);
+        }
+
+        let mut alice_copy = alice.clone();
+        let disclosed = QuantumCryptography::reconcile(&mut alice_copy, &mut bob);
+        assert_eq!(bob, alice);
+        assert_eq!(alice_copy, alice); // Only Bob's key is corrected
+        assert!(disclosed > 0);
+
+        // Matching keys only cost the parities of the clean passes
+        let blocks = alice.len() * 8 / CASCADE_BLOCK_BITS;
+        assert_eq!(QuantumCryptography::reconcile(&mut alice_copy, &mut bob), CASCADE_CLEAN_PASSES * blocks);
+        assert_eq!(bob, alice);
+    }
+
+    #[test]
+    fn aes_gcm_round_trips_a_long_message() {
+        let key = QuantumCryptography::generate_shared_key(3, 4);
+        let message = "quantum ".repeat(2048);
+
+        let ciphertext = QuantumCryptography::encrypt_aes_gcm(&message, &key);
+        assert_eq!(ciphertext.len(), AES_GCM_NONCE_LENGTH + message.len() + 16); // 16-byte tag
+        assert_eq!(QuantumCryptography::decrypt_aes_gcm(&ciphertext, &key).unwrap(), message);
+        assert_ne!(QuantumCryptography::encrypt_aes_gcm(&message, &key), ciphertext); // Fresh nonce
+    }
+
+    #[test]
+    fn aes_gcm_rejects_tampered_ciphertexts() {
+        let key = QuantumCryptography::generate_shared_key(3, 4);
+        let ciphertext = QuantumCryptography::encrypt_aes_gcm("authenticated", &key);
+
+        for index in [0, AES_GCM_NONCE_LENGTH, ciphertext.len() - 1] {
+            let mut tampered = ciphertext.clone();
+            tampered[index] ^= 0x01;
+            assert_eq!(
+                QuantumCryptography::decrypt_aes_gcm(&tampered, &key),
+                Err(CryptoError::AuthenticationFailed)
+            );
+        }
+        assert_eq!(
+            QuantumCryptography::decrypt_aes_gcm(&ciphertext[..AES_GCM_NONCE_LENGTH - 1], &key),
+            Err(CryptoError::AuthenticationFailed)
+        );
+        let other_key = QuantumCryptography::generate_shared_key(5, 6);
+        assert_eq!(
+            QuantumCryptography::decrypt_aes_gcm(&ciphertext, &other_key),
+            Err(CryptoError::AuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn aes_gcm_nonces_follow_a_seeded_rng() {
+        let key = QuantumCryptography::generate_shared_key(3, 4);
+        let encrypt = |seed: u64| {
+            QuantumCryptography::encrypt_aes_gcm_with_rng("seeded", &key, &mut StdRng::seed_from_u64(seed))
+        };
+
+        assert_eq!(encrypt(1), encrypt(1));
+        assert_ne!(encrypt(1), encrypt(2));
+        assert_eq!(QuantumCryptography::decrypt_aes_gcm(&encrypt(1), &key).unwrap(), "seeded");
+    }
 }
@@ -3,7 +3,19 @@
 // Purpose of this module: Implements quantum entanglement mechanisms
 // for simulating entangled quantum states within the network.
 
-use crate::core::quantum_network::{QuantumNode, QuantumState, QuantumNetwork};
+use crate::core::quantum_network::{QuantumState, QuantumNetwork};
+use crate::core::quantum_node::QuantumNode;
+use std::time::Instant;
+
+/// The four maximally entangled two-qubit Bell states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BellState {
+    PhiPlus,  // Φ+ = (|00⟩ + |11⟩)/√2: equal outcomes
+    PhiMinus, // Φ- = (|00⟩ - |11⟩)/√2: equal outcomes, opposite phase
+    PsiPlus,  // Ψ+ = (|01⟩ + |10⟩)/√2: opposite outcomes
+    PsiMinus, // Ψ- = (|01⟩ - |10⟩)/√2: opposite outcomes, the singlet
+}
 
 /// A structure to manage entanglement within the quantum network.
 pub struct QuantumEntanglement;
@@ -20,22 +32,15 @@ impl QuantumEntanglement {
     /// * `Ok(())` if the entanglement is successful.
     /// * `Err(String)` if the entanglement process fails.
     pub fn entangle_nodes(network: &mut QuantumNetwork, node_id_1: u32, node_id_2: u32) -> Result<(), String> {
-        let node_1 = network.get_node_mut(node_id_1);
-        let node_2 = network.get_node_mut(node_id_2);
-
-        if let (Some(node_1), Some(node_2)) = (node_1, node_2) {
-            // If both nodes exist, entangle them by linking their quantum states
-            let entangled_state = QuantumState::Entangled(Box::new(node_1.state.clone()));
-            node_2.state = entangled_state;
-
-            Ok(())
-        } else {
-            Err("One or both nodes were not found.".to_string())
-        }
+        // The network links the nodes' quantum states and records when the pair was entangled
+        network.entangle_nodes(node_id_1, node_id_2)
     }
 
     /// Checks if two nodes are entangled.
     ///
+    /// Detection uses the nodes' link lists, which mirror the network's entanglement
+    /// registry, so it does not depend on the nodes' (possibly gate-modified) states.
+    ///
     /// # Arguments
     /// * `node_1` - A reference to the first quantum node.
     /// * `node_2` - A reference to the second quantum node.
@@ -44,16 +49,167 @@ impl QuantumEntanglement {
     /// * `true` if the nodes are entangled.
     /// * `false` otherwise.
     pub fn are_entangled(node_1: &QuantumNode, node_2: &QuantumNode) -> bool {
-        match &node_2.state {
-            QuantumState::Entangled(entangled_state) => {
-                // Compare entangled state with node_1's state
-                *entangled_state.clone() == node_1.state
+        node_1.id != node_2.id
+            && node_1.entangled_nodes.contains(&node_2.id)
+            && node_2.entangled_nodes.contains(&node_1.id)
+    }
+
+    /// Prepares two nodes in a specific Bell state.
+    ///
+    /// The pair passes the same policy checks as `entangle_nodes`, and both nodes record
+    /// which Bell state was prepared.
+    ///
+    /// # Arguments
+    /// * `network` - The mutable reference to the quantum network.
+    /// * `node_id_1` - The ID of the first node.
+    /// * `node_id_2` - The ID of the second node.
+    /// * `kind` - The Bell state to prepare.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the pair was prepared.
+    /// * `Err(String)` if the nodes are the same, missing, or fail a policy check.
+    pub fn create_bell_pair(network: &mut QuantumNetwork, node_id_1: u32, node_id_2: u32, kind: BellState) -> Result<(), String> {
+        if node_id_1 == node_id_2 {
+            return Err("A Bell pair requires two distinct nodes.".to_string());
+        }
+
+        network.entangle_nodes(node_id_1, node_id_2)?;
+        for id in [node_id_1, node_id_2] {
+            if let Some(node) = network.get_node_mut(id) {
+                node.state = QuantumState::Bell(kind);
             }
-            _ => false,
+        }
+        Ok(())
+    }
+
+    /// Returns the Bell state a node was prepared in, if any.
+    ///
+    /// # Arguments
+    /// * `node` - A reference to the quantum node.
+    ///
+    /// # Returns
+    /// * `Some(BellState)` if the node holds half of a Bell pair.
+    /// * `None` otherwise.
+    pub fn bell_state_of(node: &QuantumNode) -> Option<BellState> {
+        match node.state {
+            QuantumState::Bell(kind) => Some(kind),
+            _ => None,
         }
     }
 
-    /// Breaks the entanglement between two nodes, resetting their states.
+    /// Purifies the entanglement between two nodes by consuming several pairs.
+    ///
+    /// Models BBPSSW entanglement pumping: `count` pairs of the link's current fidelity
+    /// are consumed, one at a time, to distil a single pair of higher fidelity. Every
+    /// round is assumed to succeed (post-selected). The link keeps the distilled pair,
+    /// which counts as freshly entangled.
+    ///
+    /// # Arguments
+    /// * `network` - The mutable reference to the quantum network.
+    /// * `node_id_1` - The ID of the first node.
+    /// * `node_id_2` - The ID of the second node.
+    /// * `count` - Number of pairs consumed (at least two).
+    ///
+    /// # Returns
+    /// * `Ok(f64)` with the fidelity of the purified pair.
+    /// * `Err(String)` if the nodes are not linked, `count` is below two, or the
+    ///   fidelity is too low (at most 0.5) for purification to help.
+    pub fn purify(network: &mut QuantumNetwork, node_id_1: u32, node_id_2: u32, count: usize) -> Result<f64, String> {
+        if count < 2 {
+            return Err("Purification requires at least two pairs.".to_string());
+        }
+
+        let input = network
+            .entanglement_fidelity(node_id_1, node_id_2)
+            .ok_or(format!("Nodes {} and {} are not entangled.", node_id_1, node_id_2))?;
+        if input <= 0.5 {
+            return Err(format!("Fidelity {:.3} is too low to purify.", input));
+        }
+
+        let mut fidelity = input;
+        for _ in 1..count {
+            fidelity = Self::bbpssw_round(fidelity, input);
+        }
+
+        let link = network
+            .link_mut(node_id_1, node_id_2)
+            .ok_or(format!("Nodes {} and {} are not entangled.", node_id_1, node_id_2))?;
+        let now = Instant::now();
+        link.fidelity = fidelity;
+        link.created_at = now;
+        link.last_used = now;
+        Ok(fidelity)
+    }
+
+    /// Computes the output fidelity of one successful BBPSSW round on Werner pairs.
+    fn bbpssw_round(f1: f64, f2: f64) -> f64 {
+        let (e1, e2) = ((1.0 - f1) / 3.0, (1.0 - f2) / 3.0);
+        let success = f1 * f2 + f1 * e2 + e1 * f2 + 5.0 * e1 * e2;
+        (f1 * f2 + e1 * e2) / success
+    }
+
+    /// Entangles a group of nodes into a GHZ-like multi-party state.
+    ///
+    /// Member IDs are stored sorted, so the order of `node_ids` does not affect the
+    /// resulting group state. Every pair of members is also linked.
+    ///
+    /// # Arguments
+    /// * `network` - The mutable reference to the quantum network.
+    /// * `node_ids` - The IDs of the nodes to entangle (at least two distinct).
+    ///
+    /// # Returns
+    /// * `Ok(())` if the group was entangled.
+    /// * `Err(String)` if fewer than two distinct nodes are given or a node is missing.
+    pub fn entangle_group(network: &mut QuantumNetwork, node_ids: &[u32]) -> Result<(), String> {
+        let mut members = node_ids.to_vec();
+        members.sort_unstable();
+        members.dedup();
+
+        if members.len() < 2 {
+            return Err("A group requires at least two distinct nodes.".to_string());
+        }
+        if let Some(missing) = members.iter().find(|&&id| network.get_node(id).is_none()) {
+            return Err(format!("Node {} not found.", missing));
+        }
+
+        for (i, &node_a) in members.iter().enumerate() {
+            for &node_b in &members[i + 1..] {
+                network.link_states(node_a, node_b);
+            }
+        }
+        for &id in &members {
+            if let Some(node) = network.get_node_mut(id) {
+                node.state = QuantumState::GroupEntangled(members.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks if a set of nodes all belong to the same entangled group.
+    ///
+    /// # Arguments
+    /// * `nodes` - References to the nodes to check (at least two).
+    ///
+    /// # Returns
+    /// * `true` if every node is in the same group.
+    /// * `false` otherwise.
+    pub fn are_group_entangled(nodes: &[&QuantumNode]) -> bool {
+        let members = match nodes.first().map(|node| &node.state) {
+            Some(QuantumState::GroupEntangled(members)) if nodes.len() >= 2 => members,
+            _ => return false,
+        };
+
+        nodes.iter().all(|node| {
+            members.contains(&node.id) && matches!(&node.state, QuantumState::GroupEntangled(m) if m == members)
+        })
+    }
+
+    /// Breaks all entanglement of a node, resetting its state.
+    ///
+    /// Every link to the node is removed; partners left without any entanglement
+    /// are reset to the ground state as well. If the node belongs to a group, it
+    /// leaves the group and the remaining members stay entangled with each other.
     ///
     /// # Arguments
     /// * `network` - The mutable reference to the quantum network.
@@ -63,16 +219,198 @@ impl QuantumEntanglement {
     /// * `Ok(())` if successful.
     /// * `Err(String)` if the node is not found or not entangled.
     pub fn break_entanglement(network: &mut QuantumNetwork, node_id: u32) -> Result<(), String> {
-        if let Some(node) = network.get_node_mut(node_id) {
-            match node.state {
-                QuantumState::Entangled(_) => {
-                    node.state = QuantumState::Zero; // Reset state to ground state
-                    Ok(())
+        let node = network.get_node(node_id).ok_or("Node not found.")?;
+        let peers = node.entangled_nodes.clone();
+        let group = match &node.state {
+            QuantumState::GroupEntangled(members) => Some(members.clone()),
+            _ => None,
+        };
+        if peers.is_empty() && group.is_none() && !matches!(node.state, QuantumState::Entangled(_) | QuantumState::Bell(_)) {
+            return Err("Node is not in an entangled state.".to_string());
+        }
+
+        // Shrink the group for the remaining members; a group of one is no longer entangled
+        if let Some(mut members) = group {
+            members.retain(|&id| id != node_id);
+            for &id in &members {
+                if let Some(member) = network.get_node_mut(id) {
+                    member.state = if members.len() >= 2 {
+                        QuantumState::GroupEntangled(members.clone())
+                    } else {
+                        QuantumState::Zero
+                    };
+                }
+            }
+        }
+
+        for peer in peers {
+            network.unlink(node_id, peer);
+            if let Some(peer_node) = network.get_node_mut(peer) {
+                if peer_node.entangled_nodes.is_empty()
+                    && matches!(peer_node.state, QuantumState::Entangled(_) | QuantumState::Bell(_))
+                {
+                    peer_node.state = QuantumState::Zero;
                 }
-                _ => Err("Node is not in an entangled state.".to_string()),
             }
-        } else {
-            Err("Node not found.".to_string())
         }
+
+        if let Some(node) = network.get_node_mut(node_id) {
+            node.state = QuantumState::Zero; // Reset state to ground state
+        }
+        Ok(())
+    }
+
+    /// Performs entanglement swapping to entangle two nodes through a shared relay.
+    ///
+    /// Given `node_a` entangled with `relay` and `relay` entangled with `node_b`, the relay
+    /// performs a Bell measurement that consumes both of its links and leaves `node_a`
+    /// and `node_b` directly entangled. The new link's fidelity is the product of the
+    /// consumed links' current fidelities.
+    ///
+    /// # Arguments
+    /// * `network` - The mutable reference to the quantum network.
+    /// * `node_a` - The ID of the first end node.
+    /// * `relay` - The ID of the relay node.
+    /// * `node_b` - The ID of the second end node.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the swap succeeded.
+    /// * `Err(String)` if the nodes are not distinct, missing, or not entangled with the relay.
+    pub fn swap(network: &mut QuantumNetwork, node_a: u32, relay: u32, node_b: u32) -> Result<(), String> {
+        if node_a == node_b || relay == node_a || relay == node_b {
+            return Err("Entanglement swapping requires three distinct nodes.".to_string());
+        }
+
+        let relay_node = network.get_node(relay).ok_or("Relay node not found.")?;
+        for end in [node_a, node_b] {
+            let end_node = network.get_node(end).ok_or(format!("Node {} not found.", end))?;
+            if !relay_node.entangled_nodes.contains(&end) || !end_node.entangled_nodes.contains(&relay) {
+                return Err(format!("Node {} is not entangled with relay {}.", end, relay));
+            }
+        }
+
+        let fidelity = [node_a, node_b]
+            .iter()
+            .map(|&end| network.entanglement_fidelity(end, relay).unwrap_or(1.0))
+            .product::<f64>();
+
+        // The relay's Bell measurement consumes both of its links
+        network.unlink(node_a, relay);
+        network.unlink(relay, node_b);
+        if let Some(relay_node) = network.get_node_mut(relay) {
+            if relay_node.entangled_nodes.is_empty() {
+                relay_node.state = QuantumState::Zero;
+            }
+        }
+
+        network.link_states(node_a, node_b);
+        if let Some(link) = network.link_mut(node_a, node_b) {
+            link.fidelity = fidelity;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::qubit::Qubit;
+    use num_complex::Complex;
+
+    /// Checks whether two nodes of a network are entangled with each other.
+    fn entangled(network: &QuantumNetwork, node_id_1: u32, node_id_2: u32) -> bool {
+        QuantumEntanglement::are_entangled(network.get_node(node_id_1).unwrap(), network.get_node(node_id_2).unwrap())
+    }
+
+    #[test]
+    fn swap_links_the_ends_and_consumes_the_relay() {
+        let mut network = QuantumNetwork::line(3);
+        network.degrade_links(0, 0.4); // Link 0-1 drops to 0.7
+        network.degrade_links(2, 0.2); // Link 1-2 drops to 0.85
+
+        QuantumEntanglement::swap(&mut network, 0, 1, 2).unwrap();
+        assert!(entangled(&network, 0, 2));
+        assert!(!entangled(&network, 0, 1));
+        assert!(!entangled(&network, 1, 2));
+        assert!(network.get_node(1).unwrap().entangled_nodes.is_empty());
+        assert_eq!(network.get_node(1).unwrap().state, QuantumState::Zero);
+        assert!((network.entanglement_fidelity(0, 2).unwrap() - 0.7 * 0.85).abs() < 1e-12);
+        assert!(QuantumEntanglement::swap(&mut network, 0, 1, 2).is_err()); // The relay's links are gone
+    }
+
+    #[test]
+    fn entangle_group_links_every_member_and_sorts_the_group() {
+        let mut network = QuantumNetwork::new();
+        for id in 1..=4 {
+            network.add_node(id, (f64::from(id), 0.0), QuantumState::Zero);
+        }
+
+        QuantumEntanglement::entangle_group(&mut network, &[3, 1, 2, 1]).unwrap();
+        for id in 1..=3 {
+            assert_eq!(network.get_node(id).unwrap().state, QuantumState::GroupEntangled(vec![1, 2, 3]));
+        }
+        for (a, b) in [(1, 2), (1, 3), (2, 3)] {
+            assert!(entangled(&network, a, b));
+        }
+        let group: Vec<&QuantumNode> = (1..=3).map(|id| network.get_node(id).unwrap()).collect();
+        assert!(QuantumEntanglement::are_group_entangled(&group));
+        let outsider = [network.get_node(1).unwrap(), network.get_node(4).unwrap()];
+        assert!(!QuantumEntanglement::are_group_entangled(&outsider));
+
+        QuantumEntanglement::break_entanglement(&mut network, 2).unwrap();
+        for id in [1, 3] {
+            assert_eq!(network.get_node(id).unwrap().state, QuantumState::GroupEntangled(vec![1, 3]));
+        }
+        assert!(QuantumEntanglement::entangle_group(&mut network, &[1, 1]).is_err());
+        assert!(QuantumEntanglement::entangle_group(&mut network, &[1, 9]).is_err());
+    }
+
+    #[test]
+    fn psi_minus_pairs_read_back_as_psi_minus() {
+        let mut network = QuantumNetwork::line(2);
+        network.add_node(2, (5.0, 0.0), QuantumState::Zero);
+
+        QuantumEntanglement::create_bell_pair(&mut network, 0, 2, BellState::PsiMinus).unwrap();
+        for id in [0, 2] {
+            assert_eq!(QuantumEntanglement::bell_state_of(network.get_node(id).unwrap()), Some(BellState::PsiMinus));
+        }
+        assert!(entangled(&network, 0, 2));
+        assert_eq!(QuantumEntanglement::bell_state_of(network.get_node(1).unwrap()), None); // A plain link
+        assert!(QuantumEntanglement::create_bell_pair(&mut network, 2, 2, BellState::PsiMinus).is_err());
+    }
+
+    #[test]
+    fn gates_on_the_states_do_not_hide_the_entanglement() {
+        let mut network = QuantumNetwork::line(2);
+        let mut qubit = Qubit::new(Complex::new(0.6, 0.0), Complex::new(0.0, 0.8)).unwrap();
+        qubit.apply_x();
+        network.get_node_mut(0).unwrap().state = QuantumState::Superposition(qubit.clone());
+        qubit.apply_z();
+        network.get_node_mut(1).unwrap().state = QuantumState::Superposition(qubit);
+        assert_ne!(network.get_node(0).unwrap().state, network.get_node(1).unwrap().state);
+
+        assert!(entangled(&network, 0, 1));
+        assert!(network.link(0, 1).is_some());
+
+        network.get_node_mut(1).unwrap().state = QuantumState::One; // Measured
+        assert!(entangled(&network, 0, 1));
+    }
+
+    #[test]
+    fn purification_raises_the_fidelity() {
+        let mut network = QuantumNetwork::line(2);
+        network.link_mut(0, 1).unwrap().fidelity = 0.8;
+
+        let two_pairs = QuantumEntanglement::purify(&mut network, 0, 1, 2).unwrap();
+        assert!(two_pairs > 0.8, "{}", two_pairs);
+        assert!((network.entanglement_fidelity(0, 1).unwrap() - two_pairs).abs() < 1e-12);
+
+        network.link_mut(0, 1).unwrap().fidelity = 0.8;
+        let three_pairs = QuantumEntanglement::purify(&mut network, 0, 1, 3).unwrap();
+        assert!(three_pairs > two_pairs, "{} <= {}", three_pairs, two_pairs);
+
+        assert!(QuantumEntanglement::purify(&mut network, 0, 1, 1).is_err());
+        network.link_mut(0, 1).unwrap().fidelity = 0.5;
+        assert!(QuantumEntanglement::purify(&mut network, 0, 1, 2).is_err());
     }
 }
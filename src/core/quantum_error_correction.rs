@@ -3,17 +3,272 @@
 // Purpose of this module: Provides error correction techniques for preserving
 // quantum information by detecting and correcting quantum errors.
 
-use crate::core::quantum_network::{QuantumNode, QuantumState};
+use crate::core::quantum_network::QuantumState;
+use crate::core::quantum_node::QuantumNode;
+use crate::core::qubit::Qubit;
+use num_complex::Complex;
 use rand::Rng;
 
+/// Largest amplitude difference `detect_error` treats as numerical noise rather than an error.
+pub const STATE_TOLERANCE: f64 = 1e-9;
+
+/// Number of physical qubits in a Steane code block.
+pub const STEANE_QUBITS: usize = 7;
+
+/// Number of codewords in each of the Steane code's logical basis states.
+const STEANE_CODEWORDS: f64 = 8.0;
+
+/// Block lengths whose Hamming parity checks each cover an even number of qubits.
+const SYNDROME_BLOCK_LENGTHS: [usize; 2] = [3, 7];
+
 /// Represents different types of quantum errors that can occur.
+///
+/// With the `serde` feature, serializes to the stable names `"bit_flip"`, `"phase_flip"` and `"depolarizing"`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum QuantumError {
     BitFlip,  // X error: Flips the quantum state |0> ↔ |1>
     PhaseFlip, // Z error: Alters the phase of a quantum state
     Depolarizing, // Randomizes the state
 }
 
+/// Errors that can occur while constructing a noise model.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NoiseModelError {
+    InvalidProbability(f64), // A probability is negative, above 1, or not a number
+    TotalExceedsOne(f64),    // The error probabilities sum to more than 1
+}
+
+/// Weighted distribution of the errors a noisy channel introduces.
+///
+/// Each call samples at most one error; with the remaining probability
+/// `1 - (bit_flip + phase_flip + depolarizing)` no error occurs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseModel {
+    bit_flip_prob: f64,     // Probability of a BitFlip error
+    phase_flip_prob: f64,   // Probability of a PhaseFlip error
+    depolarizing_prob: f64, // Probability of a Depolarizing error
+}
+
+impl NoiseModel {
+    /// Creates a noise model from per-error probabilities.
+    ///
+    /// # Arguments
+    /// * `bit_flip_prob` - Probability of a bit-flip error.
+    /// * `phase_flip_prob` - Probability of a phase-flip error.
+    /// * `depolarizing_prob` - Probability of a depolarizing error.
+    ///
+    /// # Returns
+    /// * `Ok(NoiseModel)` if every probability is in [0, 1] and they sum to at most 1.
+    /// * `Err(NoiseModelError)` otherwise.
+    pub fn new(bit_flip_prob: f64, phase_flip_prob: f64, depolarizing_prob: f64) -> Result<Self, NoiseModelError> {
+        for prob in [bit_flip_prob, phase_flip_prob, depolarizing_prob] {
+            if !(0.0..=1.0).contains(&prob) {
+                return Err(NoiseModelError::InvalidProbability(prob));
+            }
+        }
+
+        let total = bit_flip_prob + phase_flip_prob + depolarizing_prob;
+        if total > 1.0 {
+            return Err(NoiseModelError::TotalExceedsOne(total));
+        }
+
+        Ok(NoiseModel {
+            bit_flip_prob,
+            phase_flip_prob,
+            depolarizing_prob,
+        })
+    }
+
+    /// Returns the probability of a bit-flip error.
+    pub fn bit_flip_prob(&self) -> f64 {
+        self.bit_flip_prob
+    }
+
+    /// Returns the probability of a phase-flip error.
+    pub fn phase_flip_prob(&self) -> f64 {
+        self.phase_flip_prob
+    }
+
+    /// Returns the probability of a depolarizing error.
+    pub fn depolarizing_prob(&self) -> f64 {
+        self.depolarizing_prob
+    }
+
+    /// Samples an error from the model.
+    ///
+    /// # Arguments
+    /// * `rng` - The random number generator to draw from.
+    ///
+    /// # Returns
+    /// * `Some(QuantumError)` - The sampled error.
+    /// * `None` - If no error occurs.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Option<QuantumError> {
+        let draw = rng.gen::<f64>();
+        if draw < self.bit_flip_prob {
+            Some(QuantumError::BitFlip)
+        } else if draw < self.bit_flip_prob + self.phase_flip_prob {
+            Some(QuantumError::PhaseFlip)
+        } else if draw < self.bit_flip_prob + self.phase_flip_prob + self.depolarizing_prob {
+            Some(QuantumError::Depolarizing)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for NoiseModel {
+    /// An error always occurs, chosen uniformly among the three types.
+    fn default() -> Self {
+        NoiseModel {
+            bit_flip_prob: 1.0 / 3.0,
+            phase_flip_prob: 1.0 / 3.0,
+            depolarizing_prob: 1.0 / 3.0,
+        }
+    }
+}
+
+/// Parity-check outcomes of a code block, localizing errors without revealing the logical state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Syndrome {
+    pub bit_flip: usize,   // 1-based position of the bit-flipped qubit, 0 if none
+    pub phase_flip: usize, // 1-based position of the phase-flipped qubit, 0 if none
+}
+
+impl Syndrome {
+    /// Returns `true` if no parity check fired.
+    pub fn is_trivial(&self) -> bool {
+        self.bit_flip == 0 && self.phase_flip == 0
+    }
+
+    /// Checks whether the syndrome flags the qubit at a 0-based position.
+    fn flags(&self, position: usize) -> bool {
+        position + 1 == self.bit_flip || position + 1 == self.phase_flip
+    }
+}
+
+/// The joint state of the seven physical qubits of a Steane [[7,1,3]] code block.
+///
+/// Bit `i` of an amplitude's index is the computational-basis value of physical qubit `i`.
+/// Encoded states are entangled across the block, so they cannot be held as seven
+/// independent `QuantumState`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SteaneBlock {
+    amplitudes: Vec<Complex<f64>>, // 2^7 amplitudes, one per basis state of the block
+}
+
+impl SteaneBlock {
+    /// Applies a bit-flip (X) error to one physical qubit.
+    ///
+    /// # Arguments
+    /// * `qubit` - The 0-based position of the physical qubit (below `STEANE_QUBITS`).
+    ///
+    /// # Panics
+    /// If `qubit` is outside the block.
+    pub fn apply_x(&mut self, qubit: usize) {
+        assert!(qubit < STEANE_QUBITS, "Steane blocks have {} qubits", STEANE_QUBITS);
+        let mask = 1 << qubit;
+        for index in 0..self.amplitudes.len() {
+            if index & mask == 0 {
+                self.amplitudes.swap(index, index | mask);
+            }
+        }
+    }
+
+    /// Applies a phase-flip (Z) error to one physical qubit.
+    ///
+    /// # Arguments
+    /// * `qubit` - The 0-based position of the physical qubit (below `STEANE_QUBITS`).
+    ///
+    /// # Panics
+    /// If `qubit` is outside the block.
+    pub fn apply_z(&mut self, qubit: usize) {
+        assert!(qubit < STEANE_QUBITS, "Steane blocks have {} qubits", STEANE_QUBITS);
+        let mask = 1 << qubit;
+        for (index, amplitude) in self.amplitudes.iter_mut().enumerate() {
+            if index & mask != 0 {
+                *amplitude = -*amplitude;
+            }
+        }
+    }
+
+    /// Measures the block's stabilizers.
+    ///
+    /// The three Z-type stabilizers locate a bit-flip and the three X-type stabilizers
+    /// locate a phase-flip. Each stabilizer acts on the qubits whose 1-based position has
+    /// the corresponding bit set, so the failed checks spell out the faulty position.
+    ///
+    /// # Returns
+    /// * `Syndrome` - The positions of at most one bit-flip and one phase-flip.
+    pub fn syndrome(&self) -> Syndrome {
+        let mut syndrome = Syndrome::default();
+        for (check, mask) in hamming_check_masks().into_iter().enumerate() {
+            let z_expectation: f64 = self
+                .amplitudes
+                .iter()
+                .enumerate()
+                .map(|(index, amplitude)| parity_sign(index & mask) * amplitude.norm_sqr())
+                .sum();
+            let x_expectation: f64 = self
+                .amplitudes
+                .iter()
+                .enumerate()
+                .map(|(index, amplitude)| (amplitude.conj() * self.amplitudes[index ^ mask]).re)
+                .sum();
+            if z_expectation < 0.0 {
+                syndrome.bit_flip |= 1 << check;
+            }
+            if x_expectation < 0.0 {
+                syndrome.phase_flip |= 1 << check;
+            }
+        }
+        syndrome
+    }
+
+    /// Undoes the errors a syndrome points at.
+    ///
+    /// # Arguments
+    /// * `syndrome` - The syndrome returned by `syndrome` for this block.
+    ///
+    /// # Returns
+    /// * `true` if a correction was applied, `false` if the syndrome is trivial.
+    pub fn correct(&mut self, syndrome: &Syndrome) -> bool {
+        if let Some(qubit) = syndrome.bit_flip.checked_sub(1) {
+            self.apply_x(qubit);
+        }
+        if let Some(qubit) = syndrome.phase_flip.checked_sub(1) {
+            self.apply_z(qubit);
+        }
+        !syndrome.is_trivial()
+    }
+}
+
+/// Returns the masks of the three Hamming [7,4,3] parity checks over the block's qubits.
+///
+/// Check `r` covers every qubit whose 1-based position has bit `r` set.
+fn hamming_check_masks() -> [usize; 3] {
+    std::array::from_fn(|check| {
+        (0..STEANE_QUBITS)
+            .filter(|qubit| ((qubit + 1) >> check) & 1 == 1)
+            .fold(0, |mask, qubit| mask | (1 << qubit))
+    })
+}
+
+/// Returns `1.0` for an even number of set bits and `-1.0` for an odd number.
+fn parity_sign(bits: usize) -> f64 {
+    if bits.count_ones() % 2 == 0 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+/// Checks whether a basis state of the block is a codeword of the Hamming [7,4,3] code.
+fn is_hamming_codeword(index: usize) -> bool {
+    hamming_check_masks().iter().all(|&mask| (index & mask).count_ones() % 2 == 0)
+}
+
 /// A structure that handles quantum error correction.
 pub struct QuantumErrorCorrection;
 
@@ -22,11 +277,11 @@ impl QuantumErrorCorrection {
     ///
     /// # Arguments
     /// * `node` - A mutable reference to the quantum node.
+    /// * `rng` - The source of randomness for the error type.
     ///
     /// # Returns
     /// * `QuantumError` - The type of error applied.
-    pub fn introduce_error(node: &mut QuantumNode) -> QuantumError {
-        let mut rng = rand::thread_rng();
+    pub fn introduce_error<R: Rng>(node: &mut QuantumNode, rng: &mut R) -> QuantumError {
         let error_type = rng.gen_range(0..=2);
 
         let error = match error_type {
@@ -35,30 +290,200 @@ impl QuantumErrorCorrection {
             _ => QuantumError::Depolarizing,
         };
 
+        QuantumErrorCorrection::apply_error(node, &error);
+        error
+    }
+
+    /// Applies a specific quantum error to a node's quantum state.
+    ///
+    /// # Arguments
+    /// * `node` - A mutable reference to the quantum node.
+    /// * `error` - The error to apply.
+    pub fn apply_error(node: &mut QuantumNode, error: &QuantumError) {
         match error {
             QuantumError::BitFlip => {
-                if let QuantumState::Zero = node.state {
-                    node.state = QuantumState::One;
-                } else {
-                    node.state = QuantumState::Zero;
-                }
+                node.state = match &node.state {
+                    QuantumState::Zero => QuantumState::One,
+                    QuantumState::Superposition(qubit) => {
+                        let mut flipped = qubit.clone();
+                        flipped.apply_x();
+                        QuantumState::Superposition(flipped)
+                    }
+                    _ => QuantumState::Zero,
+                };
             }
             QuantumError::PhaseFlip => {
-                if let QuantumState::Entangled(_) = node.state {
+                if let QuantumState::Superposition(qubit) = &mut node.state {
+                    qubit.apply_z();
+                } else if let QuantumState::Entangled(_) = node.state {
                     // Simulate phase flip by disrupting entanglement
                     node.state = QuantumState::Zero;
-                }
+                } // Basis states only pick up a global phase
             }
             QuantumError::Depolarizing => {
                 node.state = QuantumState::Zero; // Reset to base state for simplicity
             }
         }
+    }
 
-        error
+    /// Encodes a logical state into three physical copies (3-qubit repetition code).
+    ///
+    /// # Arguments
+    /// * `state` - The logical state to protect.
+    ///
+    /// # Returns
+    /// * `[QuantumState; 3]` - The three physical qubits carrying the state.
+    pub fn encode_repetition(state: &QuantumState) -> [QuantumState; 3] {
+        [state.clone(), state.clone(), state.clone()]
+    }
+
+    /// Decodes a 3-qubit repetition code by majority vote.
+    ///
+    /// Any single bit-flip among the three physical qubits is corrected without
+    /// knowledge of the original state. Qubits whose amplitudes agree within
+    /// `STATE_TOLERANCE` count as holding the same state.
+    ///
+    /// # Arguments
+    /// * `qubits` - The three physical qubits produced by `encode_repetition`.
+    ///
+    /// # Returns
+    /// * `QuantumState` - The state held by at least two of the qubits, or the first
+    ///   qubit's state if all three disagree.
+    pub fn decode_repetition(qubits: &[QuantumState; 3]) -> QuantumState {
+        let [first, second, third] = qubits;
+        let same = |a: &QuantumState, b: &QuantumState| a.approx_eq(b, STATE_TOLERANCE);
+        if same(first, second) || same(first, third) {
+            first.clone()
+        } else if same(second, third) {
+            second.clone()
+        } else {
+            first.clone() // No majority: more than one error occurred
+        }
+    }
+
+    /// Encodes a logical state into a Steane [[7,1,3]] code block.
+    ///
+    /// |0⟩ maps to the equal superposition of the even-weight Hamming [7,4,3] codewords and
+    /// |1⟩ to that of the odd-weight codewords, so any single bit-flip or phase-flip on one
+    /// physical qubit can be located by the stabilizers and corrected.
+    ///
+    /// # Arguments
+    /// * `state` - The logical state to protect.
+    ///
+    /// # Returns
+    /// * `Some(SteaneBlock)` - The encoded block.
+    /// * `None` - If the state has no single-qubit description (e.g. it is entangled).
+    pub fn encode_steane(state: &QuantumState) -> Option<SteaneBlock> {
+        let qubit = state.to_qubit()?;
+        let scale = 1.0 / STEANE_CODEWORDS.sqrt();
+        let amplitudes = (0..1usize << STEANE_QUBITS)
+            .map(|index| match (is_hamming_codeword(index), index.count_ones() % 2) {
+                (false, _) => Complex::new(0.0, 0.0),
+                (true, 0) => qubit.alpha() * scale,
+                (true, _) => qubit.beta() * scale,
+            })
+            .collect();
+        Some(SteaneBlock { amplitudes })
+    }
+
+    /// Decodes a Steane code block, correcting a single bit-flip and a single phase-flip.
+    ///
+    /// The stabilizers are measured, the located errors are undone, and the logical
+    /// amplitudes are read back from the two codeword superpositions.
+    ///
+    /// # Arguments
+    /// * `block` - The block produced by `encode_steane`, possibly with errors applied.
+    ///
+    /// # Returns
+    /// * `QuantumState` - The recovered logical state.
+    pub fn decode_steane(block: &SteaneBlock) -> QuantumState {
+        let mut corrected = block.clone();
+        let syndrome = corrected.syndrome();
+        corrected.correct(&syndrome);
+
+        let scale = 1.0 / STEANE_CODEWORDS.sqrt();
+        let (mut alpha, mut beta) = (Complex::new(0.0, 0.0), Complex::new(0.0, 0.0));
+        for (index, amplitude) in corrected.amplitudes.iter().enumerate() {
+            if is_hamming_codeword(index) {
+                if index.count_ones() % 2 == 0 {
+                    alpha += *amplitude * scale;
+                } else {
+                    beta += *amplitude * scale;
+                }
+            }
+        }
+
+        let norm = (alpha.norm_sqr() + beta.norm_sqr()).sqrt();
+        Qubit::new(alpha / norm, beta / norm).map_or(QuantumState::Zero, QuantumState::from_qubit)
+    }
+
+    /// Measures the bit-flip and phase-flip syndromes of a code block.
+    ///
+    /// Each syndrome is the result of the Hamming parity checks over every qubit's Pauli
+    /// difference relative to the first qubit; only relative differences are used, so the
+    /// logical state itself is never revealed. Because every parity check covers an even
+    /// number of qubits, the result does not depend on whether the reference qubit is itself
+    /// faulty. This holds for Hamming block lengths, i.e. 3- and 7-qubit repetition blocks;
+    /// other lengths are rejected, since their syndromes could point at the wrong qubit.
+    ///
+    /// # Arguments
+    /// * `qubits` - The physical qubits of the block.
+    ///
+    /// # Returns
+    /// * `Some(Syndrome)` - The positions of at most one bit-flip and one phase-flip.
+    /// * `None` - If the block is not 3 or 7 qubits long.
+    pub fn measure_syndrome(qubits: &[QuantumState]) -> Option<Syndrome> {
+        if !SYNDROME_BLOCK_LENGTHS.contains(&qubits.len()) {
+            return None;
+        }
+
+        let mut syndrome = Syndrome::default();
+        let reference = &qubits[0];
+
+        for (position, qubit) in qubits.iter().enumerate() {
+            let (bit_flip, phase_flip) = QuantumErrorCorrection::pauli_difference(reference, qubit);
+            if bit_flip {
+                syndrome.bit_flip ^= position + 1;
+            }
+            if phase_flip {
+                syndrome.phase_flip ^= position + 1;
+            }
+        }
+        Some(syndrome)
+    }
+
+    /// Finds which Pauli errors (bit-flip, phase-flip) turn `reference` into `state`.
+    ///
+    /// States that no combination of X and Z relates, such as a depolarized qubit or
+    /// differing entangled states, are reported as having both errors.
+    fn pauli_difference(reference: &QuantumState, state: &QuantumState) -> (bool, bool) {
+        if reference.approx_eq(state, STATE_TOLERANCE) {
+            return (false, false);
+        }
+        let (reference_qubit, qubit) = match (reference.to_qubit(), state.to_qubit()) {
+            (Some(reference_qubit), Some(qubit)) => (reference_qubit, qubit),
+            _ => return (true, true),
+        };
+
+        for (bit_flip, phase_flip) in [(true, false), (false, true), (true, true)] {
+            let mut candidate = reference_qubit.clone();
+            if bit_flip {
+                candidate.apply_x();
+            }
+            if phase_flip {
+                candidate.apply_z();
+            }
+            if QuantumState::from_qubit(candidate).approx_eq(&QuantumState::from_qubit(qubit.clone()), STATE_TOLERANCE) {
+                return (bit_flip, phase_flip);
+            }
+        }
+        (true, true)
     }
 
     /// Detects if an error has occurred in a given quantum node.
     ///
+    /// Amplitudes within `STATE_TOLERANCE` of the original are not reported as an error.
+    ///
     /// # Arguments
     /// * `original_state` - The expected original quantum state.
     /// * `current_state` - The current quantum state after transmission.
@@ -67,14 +492,45 @@ impl QuantumErrorCorrection {
     /// * `Some(QuantumError)` if an error is detected.
     /// * `None` if no error is found.
     pub fn detect_error(original_state: &QuantumState, current_state: &QuantumState) -> Option<QuantumError> {
-        if original_state == current_state {
+        if original_state.approx_eq(current_state, STATE_TOLERANCE) {
             None
         } else {
             Some(QuantumError::Depolarizing) // Generic error detection
         }
     }
 
-    /// Corrects a quantum error in a node based on a redundancy check.
+    /// Corrects the qubits of a code block flagged by a measured syndrome.
+    ///
+    /// Each flagged qubit is re-prepared from a qubit the syndrome does not flag, so the
+    /// original state is never needed.
+    ///
+    /// # Arguments
+    /// * `qubits` - The physical qubits of the block.
+    /// * `syndrome` - The syndrome returned by `measure_syndrome` for the same block.
+    ///
+    /// # Returns
+    /// * `true` if a flagged qubit was corrected.
+    /// * `false` if the syndrome is trivial or points outside the block.
+    pub fn correct_error(qubits: &mut [QuantumState], syndrome: &Syndrome) -> bool {
+        let intact = match (0..qubits.len()).find(|&position| !syndrome.flags(position)) {
+            Some(position) => qubits[position].clone(),
+            None => return false,
+        };
+
+        let mut corrected = false;
+        for position in [syndrome.bit_flip, syndrome.phase_flip] {
+            if let Some(qubit) = position.checked_sub(1).and_then(|index| qubits.get_mut(index)) {
+                *qubit = intact.clone();
+                corrected = true;
+            }
+        }
+        corrected
+    }
+
+    /// Restores a single node's state when it differs from a known expected state.
+    ///
+    /// A lone node has no redundant qubits to measure a syndrome from, so this relies on
+    /// the expected state being known; code blocks should use `correct_error` instead.
     ///
     /// # Arguments
     /// * `node` - A mutable reference to the quantum node.
@@ -83,7 +539,7 @@ impl QuantumErrorCorrection {
     /// # Returns
     /// * `true` if the error was successfully corrected.
     /// * `false` if correction was unsuccessful.
-    pub fn correct_error(node: &mut QuantumNode, expected_state: &QuantumState) -> bool {
+    pub fn restore_state(node: &mut QuantumNode, expected_state: &QuantumState) -> bool {
         if let Some(_) = QuantumErrorCorrection::detect_error(expected_state, &node.state) {
             node.state = expected_state.clone(); // Restore the expected state
             true
@@ -92,3 +548,168 @@ impl QuantumErrorCorrection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Returns 0.6|0⟩ + 0.8|1⟩, which both bit-flips and phase-flips visibly change.
+    fn superposition() -> QuantumState {
+        QuantumState::from_qubit(Qubit::new(Complex::new(0.6, 0.0), Complex::new(0.8, 0.0)).unwrap())
+    }
+
+    /// Builds a 7-qubit repetition block holding copies of a state.
+    fn copies(state: &QuantumState) -> [QuantumState; 7] {
+        std::array::from_fn(|_| state.clone())
+    }
+
+    /// Builds a repetition block of 0.6|0⟩ + 0.8|1⟩.
+    fn superposition_block() -> [QuantumState; 7] {
+        copies(&superposition())
+    }
+
+    /// Applies the given Pauli errors to a single physical qubit.
+    fn flip(state: &QuantumState, bit_flip: bool, phase_flip: bool) -> QuantumState {
+        let mut qubit = state.to_qubit().unwrap();
+        if bit_flip {
+            qubit.apply_x();
+        }
+        if phase_flip {
+            qubit.apply_z();
+        }
+        QuantumState::from_qubit(qubit)
+    }
+
+    #[test]
+    fn intact_block_has_trivial_syndrome() {
+        assert!(QuantumErrorCorrection::measure_syndrome(&superposition_block()).unwrap().is_trivial());
+    }
+
+    #[test]
+    fn bit_flip_syndrome_locates_the_qubit() {
+        let mut block = copies(&QuantumState::Zero);
+        block[4] = flip(&block[4], true, false);
+
+        let syndrome = QuantumErrorCorrection::measure_syndrome(&block).unwrap();
+        assert_eq!(syndrome, Syndrome { bit_flip: 5, phase_flip: 0 });
+    }
+
+    #[test]
+    fn phase_flip_syndrome_locates_the_qubit() {
+        let mut block = superposition_block();
+        block[2] = flip(&block[2], false, true);
+
+        let syndrome = QuantumErrorCorrection::measure_syndrome(&block).unwrap();
+        assert_eq!(syndrome, Syndrome { bit_flip: 0, phase_flip: 3 });
+    }
+
+    #[test]
+    fn syndrome_locates_an_error_on_the_reference_qubit() {
+        let mut block = copies(&QuantumState::Zero);
+        block[0] = flip(&block[0], true, false);
+
+        let syndrome = QuantumErrorCorrection::measure_syndrome(&block).unwrap();
+        assert_eq!(syndrome, Syndrome { bit_flip: 1, phase_flip: 0 });
+    }
+
+    #[test]
+    fn syndrome_rejects_unsupported_block_lengths() {
+        for length in [0, 1, 2, 4, 5, 6, 8, 15] {
+            let block = vec![QuantumState::Zero; length];
+            assert_eq!(QuantumErrorCorrection::measure_syndrome(&block), None, "length {}", length);
+        }
+
+        let mut block = vec![QuantumState::Zero; 3];
+        block[1] = QuantumState::One;
+        assert_eq!(
+            QuantumErrorCorrection::measure_syndrome(&block),
+            Some(Syndrome { bit_flip: 2, phase_flip: 0 })
+        );
+    }
+
+    #[test]
+    fn correct_error_restores_the_block() {
+        let encoded = superposition_block();
+        let mut block = encoded.clone();
+        block[1] = flip(&block[1], true, false);
+        block[6] = flip(&block[6], false, true);
+
+        let syndrome = QuantumErrorCorrection::measure_syndrome(&block).unwrap();
+        assert!(QuantumErrorCorrection::correct_error(&mut block, &syndrome));
+        for (corrected, original) in block.iter().zip(encoded.iter()) {
+            assert!(corrected.approx_eq(original, STATE_TOLERANCE));
+        }
+    }
+
+    #[test]
+    fn steane_corrects_any_single_bit_flip() {
+        for logical in [QuantumState::Zero, QuantumState::One, superposition()] {
+            for qubit in 0..STEANE_QUBITS {
+                let mut block = QuantumErrorCorrection::encode_steane(&logical).unwrap();
+                block.apply_x(qubit);
+
+                assert_eq!(block.syndrome(), Syndrome { bit_flip: qubit + 1, phase_flip: 0 });
+                let decoded = QuantumErrorCorrection::decode_steane(&block);
+                assert!(decoded.approx_eq(&logical, STATE_TOLERANCE), "X on qubit {}", qubit);
+            }
+        }
+    }
+
+    #[test]
+    fn steane_corrects_any_single_phase_flip() {
+        for logical in [QuantumState::Zero, QuantumState::One, superposition()] {
+            for qubit in 0..STEANE_QUBITS {
+                let mut block = QuantumErrorCorrection::encode_steane(&logical).unwrap();
+                block.apply_z(qubit);
+
+                assert_eq!(block.syndrome(), Syndrome { bit_flip: 0, phase_flip: qubit + 1 });
+                let decoded = QuantumErrorCorrection::decode_steane(&block);
+                assert!(decoded.approx_eq(&logical, STATE_TOLERANCE), "Z on qubit {}", qubit);
+            }
+        }
+    }
+
+    #[test]
+    fn steane_intact_block_decodes_unchanged() {
+        let block = QuantumErrorCorrection::encode_steane(&superposition()).unwrap();
+
+        assert!(block.syndrome().is_trivial());
+        assert!(QuantumErrorCorrection::decode_steane(&block).approx_eq(&superposition(), STATE_TOLERANCE));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn quantum_errors_serialize_to_stable_names() {
+        for (error, name) in [
+            (QuantumError::BitFlip, "\"bit_flip\""),
+            (QuantumError::PhaseFlip, "\"phase_flip\""),
+            (QuantumError::Depolarizing, "\"depolarizing\""),
+        ] {
+            assert_eq!(serde_json::to_string(&error).unwrap(), name);
+            assert_eq!(serde_json::from_str::<QuantumError>(name).unwrap(), error);
+        }
+        assert!(serde_json::from_str::<QuantumError>("\"BitFlip\"").is_err());
+    }
+
+    #[test]
+    fn repetition_code_outvotes_a_single_introduced_error() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut node = QuantumNode::new(1);
+        let mut flips = 0;
+        for position in 0..3 {
+            for _ in 0..8 {
+                let mut qubits = QuantumErrorCorrection::encode_repetition(&QuantumState::One);
+                node.state = qubits[position].clone();
+                if QuantumErrorCorrection::introduce_error(&mut node, &mut rng) == QuantumError::BitFlip {
+                    flips += 1;
+                }
+                qubits[position] = node.state.clone();
+
+                assert_eq!(QuantumErrorCorrection::decode_repetition(&qubits), QuantumState::One);
+            }
+        }
+        assert!(flips > 0); // At least one run flipped a physical qubit
+    }
+}
@@ -4,28 +4,174 @@
 // Quantum networks enable quantum tunneling, entanglement-based data transfer, and quantum security.
 
 // Import necessary libraries.
-use rand::Rng;  // To generate random numbers
+use crate::core::quantum_entanglement::{BellState, QuantumEntanglement};
+use crate::core::quantum_node::QuantumNode;
+use crate::core::quantum_packet::QuantumPacket;
+use crate::core::qubit::{Qubit, NORMALIZATION_TOLERANCE};
+use crate::core::spatial_index::{SpatialGrid, DEFAULT_CELL_SIZE};
+use num_complex::Complex; // Amplitudes of the joint teleportation state
+use rand::rngs::StdRng; // Seedable generator for reproducible runs
+use rand::{Rng, SeedableRng}; // To generate random numbers
+use std::collections::{HashMap, HashSet, VecDeque}; // Leases, graph traversal and bounded log of entanglement attempts
+use std::f64::consts::FRAC_1_SQRT_2;
 use std::fmt;   // For error messages and formatting
+#[cfg(feature = "serde")]
+use std::{fs::File, io, io::BufReader, io::BufWriter, path::Path}; // Saving and loading networks
+use std::time::{Duration, Instant}; // Lease expiry
 
-// Define the structure for a Quantum Node
-#[derive(Debug, Clone)]
-pub struct QuantumNode {
-    pub id: u32,               // Node identifier
-    pub position: (f64, f64),  // 2D position of the node in space
-    pub state: QuantumState,   // Quantum state of the node
-}
+// Default time after which an unreleased entanglement lease is reclaimed
+const DEFAULT_LEASE_TIMEOUT: Duration = Duration::from_secs(30);
 
-#[derive(Debug, Clone)]
+// Default number of failed generation attempts after which entanglement gives up
+const DEFAULT_MAX_ENTANGLEMENT_ATTEMPTS: u32 = 10_000;
+
+// Distance between adjacent nodes placed by the topology builders
+const TOPOLOGY_SPACING: f64 = 1.0;
+
+// Default probability that quantum tunneling between two nodes succeeds
+const DEFAULT_TUNNELING_PROBABILITY: f64 = 0.5;
+
+// Fidelity of a fully decohered (maximally mixed) two-qubit pair
+const MIXED_STATE_FIDELITY: f64 = 0.25;
+
+// Maximum number of entanglement attempts kept in the log
+const ENTANGLEMENT_LOG_CAPACITY: usize = 1024;
+
+// Define the quantum states a node can be in (serializable with the `serde` feature)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QuantumState {
     Zero,  // Ground state
     One,   // First state
     Entangled(Box<QuantumState>), // Entangled states
+    Superposition(Qubit), // General single-qubit state α|0⟩ + β|1⟩
+    GroupEntangled(Vec<u32>), // Member of a GHZ-like group (sorted member IDs)
+    Bell(BellState), // Half of a pair prepared in a specific Bell state
+}
+
+impl QuantumState {
+    // Function to express a single-qubit state as complex amplitudes
+    // Entangled states have no single-qubit description and return None
+    pub fn to_qubit(&self) -> Option<Qubit> {
+        match self {
+            QuantumState::Zero => Some(Qubit::zero()),
+            QuantumState::One => Some(Qubit::one()),
+            QuantumState::Superposition(qubit) => Some(qubit.clone()),
+            QuantumState::Entangled(_) | QuantumState::GroupEntangled(_) | QuantumState::Bell(_) => None,
+        }
+    }
+
+    // Function to build a state from amplitudes, using Zero/One for basis states
+    pub fn from_qubit(qubit: Qubit) -> Self {
+        let probability_zero = qubit.probability_zero();
+        if (probability_zero - 1.0).abs() <= NORMALIZATION_TOLERANCE {
+            QuantumState::Zero
+        } else if probability_zero <= NORMALIZATION_TOLERANCE {
+            QuantumState::One
+        } else {
+            QuantumState::Superposition(qubit)
+        }
+    }
+
+    // Function to compare two states, allowing each amplitude to differ by up to epsilon
+    // Entangled states are compared recursively; other non-qubit states must match exactly
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        match (self, other) {
+            (QuantumState::Entangled(a), QuantumState::Entangled(b)) => a.approx_eq(b, epsilon),
+            _ => match (self.to_qubit(), other.to_qubit()) {
+                (Some(a), Some(b)) => (a.alpha() - b.alpha()).norm() <= epsilon && (a.beta() - b.beta()).norm() <= epsilon,
+                _ => self == other,
+            },
+        }
+    }
+
+    // Function to measure the state in the computational basis, collapsing it to Zero or One
+    // Entangled states cannot be measured locally and return None
+    pub fn measure<R: Rng>(&mut self, rng: &mut R) -> Option<u8> {
+        let outcome = self.to_qubit()?.measure(rng);
+        *self = if outcome == 0 { QuantumState::Zero } else { QuantumState::One };
+        Some(outcome)
+    }
+}
+
+// Policy checks run before two nodes are entangled
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntanglementCheck {
+    NodesExist,     // Both nodes are present in the network
+    WithinDistance, // Nodes are within the maximum entanglement distance
+}
+
+// Reasons an entanglement attempt can fail
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntanglementFailure {
+    NodeNotFound(u32),                            // A referenced node is missing
+    DistanceExceeded { distance: f64, max: f64 }, // Nodes are too far apart
+    InvalidFidelity(f64),                         // Target fidelity outside [0, 1)
+    ExhaustedAttempts(u32),                       // Generation gave up after this many attempts
+}
+
+// Record of a single entanglement attempt, kept for debugging failures
+#[derive(Debug, Clone)]
+pub struct EntanglementAttempt {
+    pub node_id_1: u32,
+    pub node_id_2: u32,
+    pub checks: Vec<EntanglementCheck>,       // Checks that were run, in order
+    pub failure: Option<EntanglementFailure>, // The failing check, if any
+}
+
+// Entanglement between a pair of nodes, with its metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntanglementLink {
+    pub a: u32,                // Lower node ID of the pair
+    pub b: u32,                // Higher node ID of the pair
+    pub fidelity: f64,         // Fidelity as of `last_used`; idle decay applies from then on
+    pub label: Option<String>, // Optional operator-assigned label
+    pub created_at: Instant,   // When the pair was last entangled
+    pub last_used: Instant,    // When the pair was last consumed or refreshed (QKD, leasing, purification)
+}
+
+// Exclusive claim on an entangled pair for the duration of one operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lease {
+    pub id: u64,        // Unique lease identifier
+    pub node_id_1: u32, // Lower node ID of the leased pair
+    pub node_id_2: u32, // Higher node ID of the leased pair
+}
+
+// Callbacks registered for entanglement events
+#[derive(Default)]
+struct EntanglementObservers {
+    on_entangle: Vec<Box<dyn Fn(u32, u32)>>, // Called with both node IDs when a pair is entangled
+    on_break: Vec<Box<dyn Fn(u32)>>,         // Called with each node that loses a link
+}
+
+// Callbacks cannot be printed, so only report how many are registered
+impl fmt::Debug for EntanglementObservers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EntanglementObservers")
+            .field("on_entangle", &self.on_entangle.len())
+            .field("on_break", &self.on_break.len())
+            .finish()
+    }
 }
 
 // Define the Quantum Network structure
 #[derive(Debug)]
 pub struct QuantumNetwork {
     nodes: Vec<QuantumNode>,  // List of quantum nodes in the network
+    spatial_index: SpatialGrid, // Grid index over node positions for radius queries
+    max_entanglement_distance: Option<f64>, // Distance limit for entanglement (None = unlimited)
+    entanglement_log: VecDeque<EntanglementAttempt>, // Most recent entanglement attempts
+    leases: HashMap<(u32, u32), (u64, Instant)>, // Active leases per pair: (lease ID, acquired at)
+    links: HashMap<(u32, u32), EntanglementLink>, // Pairwise entanglement; node entangled_nodes lists mirror it
+    max_entanglement_age: Option<Duration>, // Age after which entanglement is too stale for QKD (None = unlimited)
+    lease_timeout: Duration, // Age after which an abandoned lease is reclaimed
+    next_lease_id: u64,      // Counter for issuing lease IDs
+    max_entanglement_attempts: u32, // Cap on generation attempts per entanglement request
+    idle_decay_time: Option<Duration>, // Time constant of idle fidelity decay (None = no decay)
+    rng: StdRng,             // Source of randomness for network operations
+    tunneling_probability: f64, // Chance that quantum_tunneling succeeds
+    observers: EntanglementObservers, // Callbacks for entanglement events
 }
 
 impl QuantumNetwork {
@@ -33,65 +179,876 @@ impl QuantumNetwork {
     pub fn new() -> Self {
         QuantumNetwork {
             nodes: Vec::new(),
+            spatial_index: SpatialGrid::new(DEFAULT_CELL_SIZE),
+            max_entanglement_distance: None,
+            entanglement_log: VecDeque::new(),
+            leases: HashMap::new(),
+            links: HashMap::new(),
+            max_entanglement_age: None,
+            lease_timeout: DEFAULT_LEASE_TIMEOUT,
+            next_lease_id: 0,
+            max_entanglement_attempts: DEFAULT_MAX_ENTANGLEMENT_ATTEMPTS,
+            idle_decay_time: None,
+            rng: StdRng::from_entropy(),
+            tunneling_probability: DEFAULT_TUNNELING_PROBABILITY,
+            observers: EntanglementObservers::default(),
         }
     }
 
+    // Function to build a line of n nodes (IDs 0..n) with neighbours entangled
+    pub fn line(n: usize) -> Self {
+        let mut network = QuantumNetwork::new();
+        for i in 0..n {
+            network.add_node(i as u32, (i as f64 * TOPOLOGY_SPACING, 0.0), QuantumState::Zero);
+        }
+        for i in 1..n {
+            network.link_states(i as u32 - 1, i as u32);
+        }
+        network
+    }
+
+    // Function to build a ring of n nodes (IDs 0..n) on a circle with neighbours entangled
+    // Rings of fewer than three nodes have no closing link and equal a line.
+    pub fn ring(n: usize) -> Self {
+        let mut network = QuantumNetwork::new();
+        let radius = TOPOLOGY_SPACING * n as f64 / (2.0 * std::f64::consts::PI);
+        for i in 0..n {
+            let angle = 2.0 * std::f64::consts::PI * i as f64 / n as f64;
+            network.add_node(i as u32, (radius * angle.cos(), radius * angle.sin()), QuantumState::Zero);
+        }
+        for i in 1..n {
+            network.link_states(i as u32 - 1, i as u32);
+        }
+        if n >= 3 {
+            network.link_states(n as u32 - 1, 0);
+        }
+        network
+    }
+
+    // Function to build a star of n nodes: hub 0 at the origin entangled with leaves 1..n on a circle
+    pub fn star(n: usize) -> Self {
+        let mut network = QuantumNetwork::new();
+        if n == 0 {
+            return network;
+        }
+
+        network.add_node(0, (0.0, 0.0), QuantumState::Zero);
+        let leaves = n - 1;
+        for i in 1..n {
+            let angle = 2.0 * std::f64::consts::PI * (i - 1) as f64 / leaves as f64;
+            network.add_node(i as u32, (TOPOLOGY_SPACING * angle.cos(), TOPOLOGY_SPACING * angle.sin()), QuantumState::Zero);
+            network.link_states(0, i as u32);
+        }
+        network
+    }
+
+    // Function to build a rows x cols grid with horizontal and vertical neighbours entangled
+    // Node IDs are assigned row by row: the node in row r, column c has ID r * cols + c.
+    pub fn grid(rows: usize, cols: usize) -> Self {
+        let mut network = QuantumNetwork::new();
+        let id = |row: usize, col: usize| (row * cols + col) as u32;
+        for row in 0..rows {
+            for col in 0..cols {
+                let position = (col as f64 * TOPOLOGY_SPACING, row as f64 * TOPOLOGY_SPACING);
+                network.add_node(id(row, col), position, QuantumState::Zero);
+            }
+        }
+        for row in 0..rows {
+            for col in 0..cols {
+                if col + 1 < cols {
+                    network.link_states(id(row, col), id(row, col + 1));
+                }
+                if row + 1 < rows {
+                    network.link_states(id(row, col), id(row + 1, col));
+                }
+            }
+        }
+        network
+    }
+
+    // Function to seed the network's random number generator
+    // With the same seed and sequence of operations, random outcomes repeat exactly
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    // Function to get the network's random number generator, for operations driven from other modules
+    pub(crate) fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    // Function to configure how long a lease may be held before it is reclaimed
+    pub fn set_lease_timeout(&mut self, timeout: Duration) {
+        self.lease_timeout = timeout;
+    }
+
+    // Function to set the maximum age of entanglement that QKD may consume
+    pub fn set_max_entanglement_age(&mut self, max_age: Option<Duration>) {
+        self.max_entanglement_age = max_age;
+    }
+
+    // Function to set how quickly idle entanglement loses fidelity
+    pub fn set_idle_decay_time(&mut self, decay_time: Option<Duration>) {
+        self.idle_decay_time = decay_time;
+    }
+
+    // Function to cap how many attempts entanglement generation makes before giving up
+    pub fn set_max_entanglement_attempts(&mut self, max_attempts: u32) {
+        self.max_entanglement_attempts = max_attempts.max(1);
+    }
+
+    // Function to limit the distance over which nodes can be entangled
+    pub fn set_max_entanglement_distance(&mut self, max_distance: Option<f64>) {
+        self.max_entanglement_distance = max_distance;
+    }
+
+    // Function to set the chance that quantum tunneling succeeds
+    // Rejects probabilities outside [0, 1], leaving the current value in place
+    pub fn set_tunneling_probability(&mut self, probability: f64) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(format!("Tunneling probability {} is outside [0, 1].", probability));
+        }
+        self.tunneling_probability = probability;
+        Ok(())
+    }
+
+    // Function to register a callback invoked with both node IDs whenever a pair becomes entangled
+    pub fn on_entangle(&mut self, f: Box<dyn Fn(u32, u32)>) {
+        self.observers.on_entangle.push(f);
+    }
+
+    // Function to register a callback invoked with a node's ID whenever it loses an entanglement link
+    // Breaking a pair calls it once for each of the two nodes
+    pub fn on_break(&mut self, f: Box<dyn Fn(u32)>) {
+        self.observers.on_break.push(f);
+    }
+
     // Function to add a new node to the quantum network
     pub fn add_node(&mut self, id: u32, position: (f64, f64), state: QuantumState) {
-        let node = QuantumNode {
-            id,
-            position,
-            state,
-        };
+        let mut node = QuantumNode::new_at(id, position);
+        node.state = state;
+        self.spatial_index.insert(id, position);
         self.nodes.push(node);
     }
 
+    // Function to save the node list (positions, states, links and keys) to a JSON file
+    #[cfg(feature = "serde")]
+    pub fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, &self.nodes).map_err(io::Error::from)
+    }
+
+    // Function to load a network saved with save_to_file
+    // Fails with InvalidData on corrupt files, duplicate node IDs or links to nodes missing
+    // from the file. Restored links are timestamped as freshly entangled; other settings
+    // start at their defaults.
+    #[cfg(feature = "serde")]
+    pub fn load_from_file(path: &Path) -> io::Result<QuantumNetwork> {
+        let reader = BufReader::new(File::open(path)?);
+        let nodes: Vec<QuantumNode> = serde_json::from_reader(reader)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let mut seen = HashSet::new();
+        if let Some(node) = nodes.iter().find(|node| !seen.insert(node.id)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Duplicate node ID {} in saved network.", node.id),
+            ));
+        }
+        for node in &nodes {
+            if let Some(peer) = node.entangled_nodes.iter().find(|&peer| !seen.contains(peer)) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Node {} is linked to node {}, which is not in the saved network.", node.id, peer),
+                ));
+            }
+        }
+
+        let mut network = QuantumNetwork::new();
+        for node in nodes {
+            network.spatial_index.insert(node.id, node.position);
+            for &peer in &node.entangled_nodes {
+                network.insert_link(node.id, peer, 1.0);
+            }
+            network.nodes.push(node);
+        }
+        Ok(network)
+    }
+
+    // Function to remove a node, breaking all entanglement that references it
+    // Partners lose their link and key to the removed node; a partner left with no
+    // entanglement is reset to the ground state. Returns the removed node.
+    pub fn remove_node(&mut self, id: u32) -> Option<QuantumNode> {
+        let index = self.nodes.iter().position(|node| node.id == id)?;
+        let _ = QuantumEntanglement::break_entanglement(self, id); // No dangling entangled states
+        let removed = self.nodes.remove(index);
+        self.spatial_index.remove(id, removed.position);
+
+        for partner in self.nodes.iter_mut() {
+            partner.key_store.remove(&id);
+            partner.key_usage.remove(&id);
+        }
+        self.invalidate_routes();
+
+        self.links.retain(|&(a, b), _| a != id && b != id);
+        self.leases.retain(|&(a, b), _| a != id && b != id);
+        Some(removed)
+    }
+
+    // Function to find the IDs of all nodes within a radius of a position
+    pub fn nodes_within(&self, center: (f64, f64), radius: f64) -> Vec<u32> {
+        self.spatial_index.within(center, radius)
+    }
+
+    // Function to change the spatial index cell size, rebuilding the index
+    // Cells close to the typical query radius give the best performance
+    pub fn set_spatial_cell_size(&mut self, cell_size: f64) {
+        self.spatial_index = SpatialGrid::new(cell_size);
+        for node in &self.nodes {
+            self.spatial_index.insert(node.id, node.position);
+        }
+    }
+
+    // Function to list the IDs of all nodes in the network
+    pub fn node_ids(&self) -> Vec<u32> {
+        self.nodes.iter().map(|node| node.id).collect()
+    }
+
+    // Function to get the Euclidean distance between two nodes
+    pub fn distance(&self, node_id_1: u32, node_id_2: u32) -> Option<f64> {
+        let node_1 = self.get_node(node_id_1)?;
+        let node_2 = self.get_node(node_id_2)?;
+        Some(distance_between(node_1.position, node_2.position))
+    }
+
+    // Function to count the entanglement links in the network
+    pub fn link_count(&self) -> usize {
+        self.links.len()
+    }
+
+    // Function to count the nodes in the network
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    // Function to get the IDs of the nodes entangled with a node (empty if it does not exist)
+    pub fn neighbors(&self, id: u32) -> Vec<u32> {
+        self.get_node(id).map_or_else(Vec::new, |node| node.entangled_nodes.clone())
+    }
+
+    // Function to check whether every node can reach every other through entanglement
+    // Uses BFS from an arbitrary node; empty and single-node networks are connected.
+    pub fn is_connected(&self) -> bool {
+        let start = match self.nodes.first() {
+            Some(node) => node.id,
+            None => return true,
+        };
+
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            for neighbor in self.neighbors(current) {
+                if self.get_node(neighbor).is_some() && visited.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        visited.len() == self.nodes.len()
+    }
+
     // Function to get a node by its ID
     pub fn get_node(&self, id: u32) -> Option<&QuantumNode> {
         self.nodes.iter().find(|&node| node.id == id)
     }
 
-    // Function to simulate entangling two nodes
+    // Function to simulate entangling two nodes; every attempt is logged
     pub fn entangle_nodes(&mut self, node_id_1: u32, node_id_2: u32) -> Result<(), String> {
-        let node_1 = self.get_node_mut(node_id_1);
-        let node_2 = self.get_node_mut(node_id_2);
+        self.run_entanglement_checks(node_id_1, node_id_2)
+            .map_err(|failure| failure.to_string())?;
+        self.link_states(node_id_1, node_id_2);
+        Ok(())
+    }
 
-        if let (Some(node_1), Some(node_2)) = (node_1, node_2) {
-            let new_state = QuantumState::Entangled(Box::new(node_1.state.clone()));
-            node_2.state = new_state; // Entangle node 2 with the state of node 1
-            Ok(())
-        } else {
-            Err("One or both nodes not found.".to_string())
+    // Function to generate entanglement of a target fidelity between two nodes
+    // Each attempt succeeds with probability (1 - target_fidelity), so higher targets
+    // need more attempts on average. Returns the number of attempts used, or
+    // ExhaustedAttempts once the configured attempt cap is reached.
+    pub fn generate_entanglement(&mut self, node_id_1: u32, node_id_2: u32, target_fidelity: f64) -> Result<u32, EntanglementFailure> {
+        if !(0.0..1.0).contains(&target_fidelity) {
+            return Err(EntanglementFailure::InvalidFidelity(target_fidelity));
+        }
+        self.run_entanglement_checks(node_id_1, node_id_2)?;
+
+        let success_probability = 1.0 - target_fidelity;
+        let mut attempts = 1;
+        while self.rng.gen::<f64>() >= success_probability {
+            if attempts >= self.max_entanglement_attempts {
+                return Err(EntanglementFailure::ExhaustedAttempts(attempts));
+            }
+            attempts += 1; // Discard the low-fidelity pair and try again
+        }
+
+        self.link_states(node_id_1, node_id_2);
+        if let Some(link) = self.links.get_mut(&pair_key(node_id_1, node_id_2)) {
+            link.fidelity = target_fidelity;
+        }
+        Ok(attempts)
+    }
+
+    // Helper function to run and log the entanglement policy checks for a pair
+    fn run_entanglement_checks(&mut self, node_id_1: u32, node_id_2: u32) -> Result<(), EntanglementFailure> {
+        let attempt = self.check_entanglement(node_id_1, node_id_2);
+        let failure = attempt.failure.clone();
+        self.record_attempt(attempt);
+        failure.map_or(Ok(()), Err)
+    }
+
+    // Helper function to link the states of two checked nodes and record a fresh link
+    pub(crate) fn link_states(&mut self, node_id_1: u32, node_id_2: u32) {
+        let node_1_state = self.get_node(node_id_1).map(|node| node.state.clone());
+        if let (Some(state), Some(node_2)) = (node_1_state, self.get_node_mut(node_id_2)) {
+            node_2.state = QuantumState::Entangled(Box::new(state)); // Entangle node 2 with the state of node 1
+            node_2.entangle_with(node_id_1);
+        }
+        if let Some(node_1) = self.get_node_mut(node_id_1) {
+            node_1.entangle_with(node_id_2);
+        }
+        self.insert_link(node_id_1, node_id_2, 1.0);
+        self.invalidate_routes();
+        for callback in &self.observers.on_entangle {
+            callback(node_id_1, node_id_2);
+        }
+    }
+
+    // Helper function to drop the entanglement link between two nodes, leaving their states untouched
+    pub(crate) fn unlink(&mut self, node_id_1: u32, node_id_2: u32) {
+        if let Some(node_1) = self.get_node_mut(node_id_1) {
+            node_1.entangled_nodes.retain(|&peer| peer != node_id_2);
+        }
+        if let Some(node_2) = self.get_node_mut(node_id_2) {
+            node_2.entangled_nodes.retain(|&peer| peer != node_id_1);
+        }
+        let removed = self.links.remove(&pair_key(node_id_1, node_id_2));
+        self.invalidate_routes();
+        if removed.is_some() {
+            for callback in &self.observers.on_break {
+                callback(node_id_1);
+                callback(node_id_2);
+            }
+        }
+    }
+
+    // Helper function to record a new link for a pair, replacing any previous one
+    fn insert_link(&mut self, node_id_1: u32, node_id_2: u32, fidelity: f64) {
+        let (a, b) = pair_key(node_id_1, node_id_2);
+        let now = Instant::now();
+        self.links.insert((a, b), EntanglementLink {
+            a,
+            b,
+            fidelity,
+            label: None,
+            created_at: now,
+            last_used: now,
+        });
+    }
+
+    // Function to list every entangled pair as (lower ID, higher ID)
+    pub fn linked_pairs(&self) -> Vec<(u32, u32)> {
+        self.links.keys().copied().collect()
+    }
+
+    // Helper function to take a pair's link out of the network without breaking it, e.g. while partitioned
+    // The pair stops being entangled for every operation; the returned record can be put back with restore_link
+    pub(crate) fn suspend_link(&mut self, node_id_1: u32, node_id_2: u32) -> Option<EntanglementLink> {
+        let link = self.links.remove(&pair_key(node_id_1, node_id_2))?;
+        for (id, peer) in [(link.a, link.b), (link.b, link.a)] {
+            if let Some(node) = self.get_node_mut(id) {
+                node.entangled_nodes.retain(|&linked| linked != peer);
+            }
+        }
+        self.invalidate_routes();
+        Some(link)
+    }
+
+    // Helper function to put back a link taken out with suspend_link, keeping its fidelity and timestamps
+    // Ignored if either node has since been removed
+    pub(crate) fn restore_link(&mut self, link: EntanglementLink) {
+        if self.get_node(link.a).is_none() || self.get_node(link.b).is_none() {
+            return;
+        }
+        for (id, peer) in [(link.a, link.b), (link.b, link.a)] {
+            if let Some(node) = self.get_node_mut(id) {
+                node.entangle_with(peer);
+            }
         }
+        self.links.insert((link.a, link.b), link);
+        self.invalidate_routes();
+    }
+
+    // Function to get the entanglement link between two nodes, in either order
+    pub fn link(&self, node_id_1: u32, node_id_2: u32) -> Option<&EntanglementLink> {
+        self.links.get(&pair_key(node_id_1, node_id_2))
+    }
+
+    // Helper function to get mutable access to the link between two nodes
+    pub(crate) fn link_mut(&mut self, node_id_1: u32, node_id_2: u32) -> Option<&mut EntanglementLink> {
+        self.links.get_mut(&pair_key(node_id_1, node_id_2))
+    }
+
+    // Function to degrade the fidelity of every link of a node after noise hits it
+    // Each link moves a fraction `loss` of the way towards the mixed-state fidelity.
+    pub fn degrade_links(&mut self, node_id: u32, loss: f64) {
+        let loss = loss.clamp(0.0, 1.0);
+        for link in self.links.values_mut().filter(|link| link.a == node_id || link.b == node_id) {
+            link.fidelity = MIXED_STATE_FIDELITY + (link.fidelity - MIXED_STATE_FIDELITY) * (1.0 - loss);
+        }
+    }
+
+    // Function to label the link between two nodes; returns false if they are not linked
+    pub fn set_link_label(&mut self, node_id_1: u32, node_id_2: u32, label: Option<String>) -> bool {
+        match self.links.get_mut(&pair_key(node_id_1, node_id_2)) {
+            Some(link) => {
+                link.label = label;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Helper function to mark a pair's link as just used
+    // The decay accrued while idle is folded into the stored fidelity, so using a pair never restores it
+    pub(crate) fn touch_link(&mut self, node_id_1: u32, node_id_2: u32) {
+        let fidelity = match self.entanglement_fidelity(node_id_1, node_id_2) {
+            Some(fidelity) => fidelity,
+            None => return,
+        };
+        if let Some(link) = self.links.get_mut(&pair_key(node_id_1, node_id_2)) {
+            link.fidelity = fidelity;
+            link.last_used = Instant::now();
+        }
+    }
+
+    // Function to get how long ago a pair was entangled
+    pub fn entanglement_age(&self, node_id_1: u32, node_id_2: u32) -> Option<Duration> {
+        self.link(node_id_1, node_id_2).map(|link| link.created_at.elapsed())
+    }
+
+    // Function to get the fidelity of a pair's entanglement after idling since it was last used
+    // Fidelity decays exponentially from the link's stored fidelity towards the mixed-state
+    // value of 0.25 with the configured time constant; None if the pair has no link.
+    pub fn entanglement_fidelity(&self, node_id_1: u32, node_id_2: u32) -> Option<f64> {
+        let link = self.link(node_id_1, node_id_2)?;
+        let idle = link.last_used.elapsed();
+        let decay = match self.idle_decay_time {
+            Some(decay_time) if !decay_time.is_zero() => (-idle.as_secs_f64() / decay_time.as_secs_f64()).exp(),
+            Some(_) => 0.0,
+            None => 1.0,
+        };
+        Some(MIXED_STATE_FIDELITY + (link.fidelity - MIXED_STATE_FIDELITY) * decay)
+    }
+
+    // Function to check whether a pair's entanglement is recent enough for QKD
+    // Pairs with no recorded entanglement time are treated as stale when a limit is set
+    pub fn is_entanglement_fresh(&self, node_id_1: u32, node_id_2: u32) -> bool {
+        match self.max_entanglement_age {
+            Some(max_age) => self
+                .entanglement_age(node_id_1, node_id_2)
+                .map_or(false, |age| age <= max_age),
+            None => true,
+        }
+    }
+
+    // Function to get the failure reason of the most recent attempt to entangle a pair
+    pub fn last_entanglement_failure(&self, node_id_1: u32, node_id_2: u32) -> Option<&EntanglementFailure> {
+        self.entanglement_log
+            .iter()
+            .rev()
+            .find(|attempt| {
+                (attempt.node_id_1, attempt.node_id_2) == (node_id_1, node_id_2)
+                    || (attempt.node_id_1, attempt.node_id_2) == (node_id_2, node_id_1)
+            })
+            .and_then(|attempt| attempt.failure.as_ref())
+    }
+
+    // Function to acquire an exclusive lease on an entangled pair
+    // Returns None if the nodes are not entangled or another consumer holds an unexpired lease
+    pub fn lease_entanglement(&mut self, node_id_1: u32, node_id_2: u32) -> Option<Lease> {
+        if !self.is_entangled_pair(node_id_1, node_id_2) {
+            return None;
+        }
+
+        let pair = pair_key(node_id_1, node_id_2);
+        if let Some((_, acquired_at)) = self.leases.get(&pair) {
+            if acquired_at.elapsed() < self.lease_timeout {
+                return None; // Pair is still leased by another consumer
+            }
+        }
+
+        let id = self.next_lease_id;
+        self.next_lease_id += 1;
+        self.leases.insert(pair, (id, Instant::now()));
+        self.touch_link(pair.0, pair.1);
+
+        Some(Lease {
+            id,
+            node_id_1: pair.0,
+            node_id_2: pair.1,
+        })
+    }
+
+    // Function to release a lease so other consumers can use the pair
+    // Returns false if the lease was already released or reclaimed
+    pub fn release_lease(&mut self, lease: Lease) -> bool {
+        let pair = (lease.node_id_1, lease.node_id_2);
+        match self.leases.get(&pair) {
+            Some((id, _)) if *id == lease.id => {
+                self.leases.remove(&pair);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Function to draw shared random bits from the entanglement between two nodes
+    // Each node measures its half of the pair. Φ± pairs give equal outcomes and Ψ± pairs
+    // opposite ones (plain links hold Φ+); with link fidelity F, each of node_id_2's bits
+    // is additionally flipped with probability 2(1 - F)/3, the Z-basis error rate of a
+    // Werner state. Bits are packed MSB-first into bytes.
+    pub fn shared_randomness(&mut self, node_id_1: u32, node_id_2: u32, n_bits: usize) -> Option<(Vec<u8>, Vec<u8>)> {
+        let fidelity = self.entanglement_fidelity(node_id_1, node_id_2)?;
+        let anticorrelated = matches!(
+            self.get_node(node_id_1).map(|node| &node.state),
+            Some(QuantumState::Bell(BellState::PsiPlus | BellState::PsiMinus))
+        );
+        let flip_probability = 2.0 * (1.0 - fidelity) / 3.0;
+
+        let mut bits_1 = vec![0u8; (n_bits + 7) / 8];
+        let mut bits_2 = bits_1.clone();
+        for i in 0..n_bits {
+            let outcome_1: bool = self.rng.gen();
+            let outcome_2 = outcome_1 ^ anticorrelated ^ (self.rng.gen::<f64>() < flip_probability);
+            if outcome_1 {
+                bits_1[i / 8] |= 0x80 >> (i % 8);
+            }
+            if outcome_2 {
+                bits_2[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        self.touch_link(node_id_1, node_id_2);
+
+        Some((bits_1, bits_2))
+    }
+
+    // Function to find a chain of entangled nodes from src to dst using BFS
+    // The path starts at src and ends at dst; None if either endpoint is missing or no
+    // chain of entanglement exists. A route from a node to itself is just Some([src]).
+    pub fn find_entanglement_path(&self, src: u32, dst: u32) -> Option<Vec<u32>> {
+        self.get_node(src)?;
+        self.get_node(dst)?;
+        if src == dst {
+            return Some(vec![src]);
+        }
+
+        let mut previous: HashMap<u32, u32> = HashMap::new();
+        let mut queue = VecDeque::from([src]);
+        previous.insert(src, src);
+
+        while let Some(current) = queue.pop_front() {
+            if current == dst {
+                let mut path = vec![dst];
+                let mut node = dst;
+                while node != src {
+                    node = previous[&node];
+                    path.push(node);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let neighbors = self.get_node(current).map(|node| node.entangled_nodes.as_slice()).unwrap_or(&[]);
+            for &neighbor in neighbors {
+                if !previous.contains_key(&neighbor) {
+                    previous.insert(neighbor, current);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+
+    // Function to find the chain of entangled nodes from src to dst with the highest fidelity
+    // Uses Dijkstra with edge cost -ln(fidelity), so the minimum-cost path maximizes the
+    // product of link fidelities, which is returned alongside the path. None if either
+    // endpoint is missing or dst is unreachable; a route from a node to itself is (vec![src], 1.0).
+    pub fn shortest_path(&self, src: u32, dst: u32) -> Option<(Vec<u32>, f64)> {
+        self.get_node(src)?;
+        self.get_node(dst)?;
+
+        let mut cost: HashMap<u32, f64> = HashMap::from([(src, 0.0)]);
+        let mut previous: HashMap<u32, u32> = HashMap::new();
+        let mut settled: HashSet<u32> = HashSet::new();
+
+        loop {
+            // Settle the cheapest node not yet settled
+            let (current, current_cost) = cost
+                .iter()
+                .filter(|(id, _)| !settled.contains(id))
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(&id, &c)| (id, c))?;
+            if current == dst {
+                break;
+            }
+            settled.insert(current);
+
+            for neighbor in self.neighbors(current) {
+                let fidelity = match self.entanglement_fidelity(current, neighbor) {
+                    Some(fidelity) if fidelity > 0.0 && !settled.contains(&neighbor) => fidelity,
+                    _ => continue,
+                };
+                let candidate = current_cost - fidelity.ln();
+                if cost.get(&neighbor).map_or(true, |&known| candidate < known) {
+                    cost.insert(neighbor, candidate);
+                    previous.insert(neighbor, current);
+                }
+            }
+        }
+
+        let mut path = vec![dst];
+        let mut node = dst;
+        while node != src {
+            node = previous[&node];
+            path.push(node);
+        }
+        path.reverse();
+        Some((path, (-cost[&dst]).exp()))
+    }
+
+    // Function to get the next hop from src towards dst along a chain of entanglement
+    // The node's cached decision is used when present; otherwise a path is computed and
+    // every node along it caches its next hop towards dst.
+    pub fn next_hop(&mut self, src: u32, dst: u32) -> Option<u32> {
+        if let Some(&hop) = self.get_node(src)?.next_hop_cache.get(&dst) {
+            return Some(hop);
+        }
+
+        let path = self.find_entanglement_path(src, dst)?;
+        for hop in path.windows(2) {
+            if let Some(node) = self.get_node_mut(hop[0]) {
+                node.next_hop_cache.insert(dst, hop[1]);
+            }
+        }
+        path.get(1).copied()
+    }
+
+    // Function to forward a packet one hop from the node currently holding it
+    // Consumes one hop of the packet's TTL; expired packets are dropped and return None,
+    // as do packets with no route towards their receiver.
+    pub fn forward_packet(&mut self, at: u32, packet: &mut QuantumPacket) -> Option<u32> {
+        if !packet.decrement_ttl() {
+            return None; // TTL expired: drop to break routing loops
+        }
+        self.next_hop(at, packet.receiver_id)
+    }
+
+    // Function to drop every node's cached routing decisions
+    // Called whenever links change, since any link can lie on a cached route.
+    pub fn invalidate_routes(&mut self) {
+        for node in &mut self.nodes {
+            node.next_hop_cache.clear();
+        }
+    }
+
+    // Helper function to check whether two nodes share entanglement, according to the link registry
+    fn is_entangled_pair(&self, node_id_1: u32, node_id_2: u32) -> bool {
+        self.link(node_id_1, node_id_2).is_some()
+    }
+
+    // Helper function to run the entanglement policy checks for a pair
+    fn check_entanglement(&self, node_id_1: u32, node_id_2: u32) -> EntanglementAttempt {
+        let mut attempt = EntanglementAttempt {
+            node_id_1,
+            node_id_2,
+            checks: vec![EntanglementCheck::NodesExist],
+            failure: None,
+        };
+
+        let (node_1, node_2) = match (self.get_node(node_id_1), self.get_node(node_id_2)) {
+            (Some(node_1), Some(node_2)) => (node_1, node_2),
+            (None, _) => {
+                attempt.failure = Some(EntanglementFailure::NodeNotFound(node_id_1));
+                return attempt;
+            }
+            (_, None) => {
+                attempt.failure = Some(EntanglementFailure::NodeNotFound(node_id_2));
+                return attempt;
+            }
+        };
+
+        if let Some(max) = self.max_entanglement_distance {
+            attempt.checks.push(EntanglementCheck::WithinDistance);
+            let distance = distance_between(node_1.position, node_2.position);
+            if distance > max {
+                attempt.failure = Some(EntanglementFailure::DistanceExceeded { distance, max });
+            }
+        }
+
+        attempt
+    }
+
+    // Helper function to append to the bounded entanglement log
+    fn record_attempt(&mut self, attempt: EntanglementAttempt) {
+        if self.entanglement_log.len() == ENTANGLEMENT_LOG_CAPACITY {
+            self.entanglement_log.pop_front();
+        }
+        self.entanglement_log.push_back(attempt);
     }
 
     // Function to simulate quantum tunneling between two nodes
     pub fn quantum_tunneling(&mut self, node_id_1: u32, node_id_2: u32) -> Result<(), String> {
-        let node_1 = self.get_node_mut(node_id_1);
-        let node_2 = self.get_node_mut(node_id_2);
+        let state_2 = match (self.get_node(node_id_1), self.get_node(node_id_2)) {
+            (Some(_), Some(node_2)) => node_2.state.clone(),
+            _ => return Err("One or both nodes not found.".to_string()),
+        };
 
-        if let (Some(node_1), Some(node_2)) = (node_1, node_2) {
-            let mut rng = rand::thread_rng();
-            let tunneling_probability: f64 = rng.gen(); // Random value for tunneling probability
+        let draw: f64 = self.rng.gen(); // Random value compared against the tunneling probability
 
-            if tunneling_probability < 0.5 {
-                // Simulate tunneling if probability is less than 0.5
-                node_1.state = node_2.state.clone();
-                Ok(())
-            } else {
-                Err("Quantum tunneling failed.".to_string())
+        if draw < self.tunneling_probability {
+            // Simulate tunneling; a probability of 1.0 always succeeds and 0.0 never does
+            if let Some(node_1) = self.get_node_mut(node_id_1) {
+                node_1.state = state_2;
             }
+            Ok(())
         } else {
-            Err("One or both nodes not found.".to_string())
+            Err("Quantum tunneling failed.".to_string())
+        }
+    }
+
+    // Function to teleport a single-qubit state from src to dst over their shared entanglement
+    // src performs a Bell measurement on the payload and its half of the pair, and sends the two
+    // classical outcome bits to dst, which applies the matching X and Z corrections to recover
+    // the payload. Pairs prepared in another Bell state are first rotated back into the Φ+
+    // frame. The entanglement between the pair is consumed.
+    pub fn teleport(&mut self, src: u32, dst: u32, payload: QuantumState) -> Result<(), String> {
+        if self.get_node(src).is_none() || self.get_node(dst).is_none() {
+            return Err("One or both nodes not found.".to_string());
+        }
+        if !self.is_entangled_pair(src, dst) {
+            return Err(format!("Nodes {} and {} do not share entanglement.", src, dst));
+        }
+        let qubit = payload
+            .to_qubit()
+            .ok_or_else(|| "Only single-qubit states can be teleported.".to_string())?;
+        let frame = match self.get_node(src).map(|node| &node.state) {
+            Some(QuantumState::Bell(kind)) => *kind,
+            _ => BellState::PhiPlus, // Plain links hold the Φ+ pair
+        };
+
+        // Joint state of payload (q0), src's half (q1) and dst's half (q2), indexed q0 q1 q2
+        let half = Complex::new(FRAC_1_SQRT_2, 0.0);
+        let pair = match frame {
+            BellState::PhiPlus => [half, Complex::default(), Complex::default(), half],
+            BellState::PhiMinus => [half, Complex::default(), Complex::default(), -half],
+            BellState::PsiPlus => [Complex::default(), half, half, Complex::default()],
+            BellState::PsiMinus => [Complex::default(), half, -half, Complex::default()],
+        };
+        let mut joint = [Complex::default(); 8];
+        for (i, amplitude) in joint.iter_mut().enumerate() {
+            let payload_amplitude = if i & 4 == 0 { qubit.alpha() } else { qubit.beta() };
+            *amplitude = payload_amplitude * pair[i & 3];
+        }
+
+        // Bell measurement at src: CNOT from q0 onto q1, then H on q0
+        for i in 4..8 {
+            if i & 2 == 0 {
+                joint.swap(i, i | 2);
+            }
+        }
+        for i in 0..4 {
+            let (low, high) = (joint[i], joint[i | 4]);
+            joint[i] = (low + high) * FRAC_1_SQRT_2;
+            joint[i | 4] = (low - high) * FRAC_1_SQRT_2;
+        }
+
+        // Sample the two classical bits (m1 from q0, m2 from q1) by the Born rule
+        let draw: f64 = self.rng.gen();
+        let mut cumulative = 0.0;
+        let mut outcome = 3;
+        for candidate in 0..4 {
+            cumulative += joint[candidate << 1].norm_sqr() + joint[(candidate << 1) | 1].norm_sqr();
+            if draw < cumulative {
+                outcome = candidate;
+                break;
+            }
+        }
+        let (m1, m2) = (outcome & 2 != 0, outcome & 1 != 0);
+
+        // dst's half collapses to P X^m2 Z^m1 |payload⟩, where P is the pair's Pauli frame
+        let (alpha, beta) = (joint[outcome << 1], joint[(outcome << 1) | 1]);
+        let norm = (alpha.norm_sqr() + beta.norm_sqr()).sqrt();
+        let mut received = Qubit::new(alpha / norm, beta / norm)?;
+        match frame {
+            BellState::PhiPlus => {}
+            BellState::PhiMinus => received.apply_z(),
+            BellState::PsiPlus => received.apply_x(),
+            BellState::PsiMinus => {
+                received.apply_x();
+                received.apply_z();
+            }
+        }
+
+        // dst applies exactly X^m2 then Z^m1, as dictated by the two classical bits
+        if m2 {
+            received.apply_x();
+        }
+        if m1 {
+            received.apply_z();
+        }
+
+        self.unlink(src, dst);
+        if let Some(node) = self.get_node_mut(src) {
+            node.state = if m2 { QuantumState::One } else { QuantumState::Zero }; // Collapsed by the measurement
+        }
+        if let Some(node) = self.get_node_mut(dst) {
+            node.state = QuantumState::from_qubit(received);
         }
+        Ok(())
     }
 
-    // Helper function to get a mutable reference to a node by ID
-    fn get_node_mut(&mut self, id: u32) -> Option<&mut QuantumNode> {
+    // Function to get a mutable reference to a node by ID
+    pub fn get_node_mut(&mut self, id: u32) -> Option<&mut QuantumNode> {
         self.nodes.iter_mut().find(|node| node.id == id)
     }
 }
 
+// Helper function to normalize a node pair into an order-independent key
+fn pair_key(node_id_1: u32, node_id_2: u32) -> (u32, u32) {
+    (node_id_1.min(node_id_2), node_id_1.max(node_id_2))
+}
+
+// Helper function to compute the Euclidean distance between two positions
+fn distance_between(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+// Implement the Display trait for readable entanglement failure reasons
+impl fmt::Display for EntanglementFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntanglementFailure::NodeNotFound(id) => write!(f, "Node {} not found.", id),
+            EntanglementFailure::DistanceExceeded { distance, max } => {
+                write!(f, "Distance {:.2} exceeds the entanglement limit of {:.2}.", distance, max)
+            }
+            EntanglementFailure::InvalidFidelity(fidelity) => {
+                write!(f, "Target fidelity {} must be in [0, 1).", fidelity)
+            }
+            EntanglementFailure::ExhaustedAttempts(attempts) => {
+                write!(f, "Entanglement generation gave up after {} attempts.", attempts)
+            }
+        }
+    }
+}
+
 // Implement the Display trait for easy printing of QuantumNetwork
 impl fmt::Display for QuantumNetwork {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -99,3 +1056,445 @@ impl fmt::Display for QuantumNetwork {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::quantum_packet::QuantumPacketType;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Builds a seeded network with two entangled nodes, 1 and 2.
+    fn entangled_pair(seed: u64) -> QuantumNetwork {
+        let mut network = QuantumNetwork::new();
+        network.set_seed(seed);
+        network.add_node(1, (0.0, 0.0), QuantumState::Zero);
+        network.add_node(2, (1.0, 0.0), QuantumState::Zero);
+        network.entangle_nodes(1, 2).unwrap();
+        network
+    }
+
+    #[test]
+    fn teleporting_one_delivers_one_and_consumes_the_pair() {
+        for seed in 0..16 {
+            let mut network = entangled_pair(seed);
+
+            network.teleport(1, 2, QuantumState::One).unwrap();
+            assert_eq!(network.get_node(2).unwrap().state, QuantumState::One);
+            assert!(!network.is_entangled_pair(1, 2));
+            assert!(network.teleport(1, 2, QuantumState::One).is_err());
+        }
+    }
+
+    #[test]
+    fn teleport_recovers_superpositions_in_every_bell_frame() {
+        let payload = QuantumState::Superposition(Qubit::new(Complex::new(0.6, 0.0), Complex::new(0.0, 0.8)).unwrap());
+        let frames = [BellState::PhiPlus, BellState::PhiMinus, BellState::PsiPlus, BellState::PsiMinus];
+        for (seed, frame) in (0..32).zip(frames.iter().cycle()) {
+            let mut network = QuantumNetwork::new();
+            network.set_seed(seed);
+            network.add_node(1, (0.0, 0.0), QuantumState::Zero);
+            network.add_node(2, (1.0, 0.0), QuantumState::Zero);
+            QuantumEntanglement::create_bell_pair(&mut network, 1, 2, *frame).unwrap();
+
+            network.teleport(1, 2, payload.clone()).unwrap();
+            assert!(network.get_node(2).unwrap().state.approx_eq(&payload, 1e-9), "{:?}, seed {}", frame, seed);
+        }
+    }
+
+    #[test]
+    fn shared_randomness_is_equal_on_a_perfect_phi_plus_pair() {
+        let draw = |seed| {
+            let mut network = entangled_pair(seed);
+            network.shared_randomness(1, 2, 256).unwrap()
+        };
+
+        let (bits_1, bits_2) = draw(11);
+        assert_eq!(bits_1, bits_2);
+        assert_eq!(draw(11), (bits_1, bits_2)); // Same seed, same bits
+    }
+
+    #[test]
+    fn shared_randomness_follows_the_bell_state_and_fidelity() {
+        let mut network = QuantumNetwork::new();
+        network.set_seed(5);
+        network.add_node(1, (0.0, 0.0), QuantumState::Zero);
+        network.add_node(2, (1.0, 0.0), QuantumState::Zero);
+        QuantumEntanglement::create_bell_pair(&mut network, 1, 2, BellState::PsiMinus).unwrap();
+        let (bits_1, bits_2) = network.shared_randomness(1, 2, 256).unwrap();
+        assert!(bits_1.iter().zip(&bits_2).all(|(a, b)| a ^ b == 0xff)); // Ψ- outcomes are opposite
+
+        let mut network = QuantumNetwork::new();
+        network.set_seed(5);
+        network.add_node(1, (0.0, 0.0), QuantumState::Zero);
+        network.add_node(2, (1.0, 0.0), QuantumState::Zero);
+        network.generate_entanglement(1, 2, 0.7).unwrap();
+        let (bits_1, bits_2) = network.shared_randomness(1, 2, 4096).unwrap();
+        let mismatches: u32 = bits_1.iter().zip(&bits_2).map(|(a, b)| (a ^ b).count_ones()).sum();
+        let rate = f64::from(mismatches) / 4096.0;
+        assert!((rate - 0.2).abs() < 0.03, "mismatch rate {}", rate); // 2(1 - 0.7)/3
+    }
+
+    #[test]
+    fn nodes_within_lists_each_node_once() {
+        let mut network = QuantumNetwork::new();
+        for id in 0..10 {
+            network.add_node(id, (f64::from(id), 0.0), QuantumState::Zero);
+        }
+
+        assert_eq!(network.nodes_within((2.0, 0.0), 1.5), vec![1, 2, 3]);
+        network.remove_node(2);
+        assert_eq!(network.nodes_within((2.0, 0.0), 1.5), vec![1, 3]);
+    }
+
+    #[test]
+    fn over_distance_entanglement_logs_distance_exceeded() {
+        let mut network = QuantumNetwork::new();
+        network.set_max_entanglement_distance(Some(5.0));
+        network.add_node(1, (0.0, 0.0), QuantumState::Zero);
+        network.add_node(2, (10.0, 0.0), QuantumState::Zero);
+        network.add_node(3, (3.0, 4.0), QuantumState::Zero);
+
+        assert!(network.entangle_nodes(1, 2).is_err());
+        assert_eq!(
+            network.last_entanglement_failure(2, 1),
+            Some(&EntanglementFailure::DistanceExceeded { distance: 10.0, max: 5.0 })
+        );
+        assert!(network.link(1, 2).is_none());
+
+        network.entangle_nodes(1, 3).unwrap(); // Exactly at the limit
+        assert_eq!(network.last_entanglement_failure(1, 3), None);
+    }
+
+    #[test]
+    fn only_one_consumer_holds_a_lease_until_release() {
+        let mut network = entangled_pair(1);
+
+        let first = network.lease_entanglement(1, 2).unwrap();
+        assert_eq!(network.lease_entanglement(2, 1), None); // The second consumer is refused
+        assert!(network.release_lease(first.clone()));
+        assert!(!network.release_lease(first)); // Releasing twice is a no-op
+
+        let second = network.lease_entanglement(2, 1).unwrap();
+        assert_eq!((second.node_id_1, second.node_id_2), (1, 2));
+        assert_eq!(network.lease_entanglement(1, 2), None);
+    }
+
+    #[test]
+    fn abandoned_leases_are_reclaimed_after_the_timeout() {
+        let mut network = entangled_pair(1);
+        network.set_lease_timeout(Duration::ZERO);
+
+        let abandoned = network.lease_entanglement(1, 2).unwrap();
+        let reclaimed = network.lease_entanglement(1, 2).unwrap();
+        assert_ne!(abandoned.id, reclaimed.id);
+        assert!(!network.release_lease(abandoned)); // The stale holder cannot release the new lease
+        assert!(network.release_lease(reclaimed));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn quantum_states_round_trip_through_json() {
+        let states = [
+            QuantumState::Zero,
+            QuantumState::One,
+            QuantumState::Entangled(Box::new(QuantumState::Entangled(Box::new(QuantumState::One)))),
+            QuantumState::Superposition(Qubit::plus()),
+            QuantumState::GroupEntangled(vec![1, 2, 3]),
+            QuantumState::Bell(BellState::PsiMinus),
+        ];
+        for state in states {
+            let json = serde_json::to_string(&state).unwrap();
+            assert_eq!(serde_json::from_str::<QuantumState>(&json).unwrap(), state, "{}", json);
+        }
+    }
+
+    #[test]
+    fn higher_target_fidelity_needs_more_attempts() {
+        let total_attempts = |target_fidelity: f64| -> u32 {
+            let mut network = entangled_pair(42);
+            (0..200).map(|_| network.generate_entanglement(1, 2, target_fidelity).unwrap()).sum()
+        };
+
+        let (low, high) = (total_attempts(0.5), total_attempts(0.9));
+        assert!(high > 2 * low, "0.9 took {} attempts, 0.5 took {}", high, low);
+        assert_eq!(total_attempts(0.9), high); // Reproducible under the seed
+    }
+
+    #[test]
+    fn generate_entanglement_records_the_target_fidelity() {
+        let mut network = entangled_pair(42);
+
+        assert!(network.generate_entanglement(1, 2, 0.8).unwrap() >= 1);
+        assert_eq!(network.link(1, 2).unwrap().fidelity, 0.8);
+        assert_eq!(
+            network.generate_entanglement(1, 2, 1.0),
+            Err(EntanglementFailure::InvalidFidelity(1.0))
+        );
+    }
+
+    #[test]
+    fn removing_an_entangled_node_resets_its_partners() {
+        let mut network = entangled_pair(1);
+        network.add_node(3, (0.0, 1.0), QuantumState::Zero);
+        network.entangle_nodes(1, 3).unwrap();
+        for partner in [2, 3] {
+            assert!(network.get_node_mut(partner).unwrap().exchange_keys(1, &[7u8; 16]));
+        }
+
+        let removed = network.remove_node(1).expect("node 1 exists");
+        assert_eq!(removed.id, 1);
+        assert_eq!(removed.state, QuantumState::Zero);
+        assert_eq!(network.node_ids(), vec![2, 3]);
+        assert_eq!(network.link_count(), 0);
+        for partner in [2, 3] {
+            let node = network.get_node(partner).unwrap();
+            assert_eq!(node.state, QuantumState::Zero);
+            assert!(node.entangled_nodes.is_empty());
+            assert!(!node.key_store.contains_key(&1));
+            assert!(!node.key_usage.contains_key(&1));
+        }
+        assert!(network.remove_node(1).is_none());
+    }
+
+    #[test]
+    fn generation_gives_up_at_the_attempt_cap() {
+        let mut network = QuantumNetwork::new();
+        network.set_seed(5);
+        network.add_node(1, (0.0, 0.0), QuantumState::Zero);
+        network.add_node(2, (1.0, 0.0), QuantumState::Zero);
+        network.set_max_entanglement_attempts(3);
+
+        // Each attempt succeeds with probability 1e-9, so only the cap ends the loop
+        assert_eq!(
+            network.generate_entanglement(1, 2, 1.0 - 1e-9),
+            Err(EntanglementFailure::ExhaustedAttempts(3))
+        );
+        assert!(!network.is_entangled_pair(1, 2));
+        assert_eq!(network.link_count(), 0);
+
+        network.set_max_entanglement_attempts(0); // Raised to a single attempt
+        assert_eq!(
+            network.generate_entanglement(1, 2, 1.0 - 1e-9),
+            Err(EntanglementFailure::ExhaustedAttempts(1))
+        );
+    }
+
+    /// Returns a path for a scratch file in the system temp directory.
+    #[cfg(feature = "serde")]
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("quantum_net_{}_{}.json", name, std::process::id()))
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn saved_networks_load_with_the_same_nodes_and_links() {
+        let mut network = entangled_pair(3);
+        network.add_node(3, (0.0, 2.0), QuantumState::One);
+        let path = temp_path("round_trip");
+
+        network.save_to_file(&path).unwrap();
+        let loaded = QuantumNetwork::load_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.node_ids(), vec![1, 2, 3]);
+        for id in 1..=3 {
+            let (original, restored) = (network.get_node(id).unwrap(), loaded.get_node(id).unwrap());
+            assert_eq!(restored.state, original.state);
+            assert_eq!(restored.position, original.position);
+        }
+        assert_eq!(loaded.linked_pairs(), vec![(1, 2)]);
+        assert_eq!(loaded.nodes_within((0.0, 2.0), 0.5), vec![3]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn loading_rejects_links_to_missing_nodes() {
+        let mut network = entangled_pair(3);
+        network.get_node_mut(2).unwrap().entangled_nodes.push(9);
+        let path = temp_path("dangling_link");
+
+        network.save_to_file(&path).unwrap();
+        let loaded = QuantumNetwork::load_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.err().map(|err| err.kind()), Some(io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn routing_handles_empty_networks_missing_nodes_and_self_routes() {
+        let empty = QuantumNetwork::new();
+        assert_eq!(empty.find_entanglement_path(1, 2), None);
+        assert_eq!(empty.shortest_path(1, 2), None);
+
+        let mut network = entangled_pair(1);
+        network.add_node(3, (5.0, 0.0), QuantumState::Zero);
+        assert_eq!(network.find_entanglement_path(1, 9), None);
+        assert_eq!(network.find_entanglement_path(9, 1), None);
+        assert_eq!(network.shortest_path(1, 9), None);
+        assert_eq!(network.shortest_path(9, 9), None);
+
+        assert_eq!(network.find_entanglement_path(1, 1), Some(vec![1]));
+        assert_eq!(network.shortest_path(1, 1), Some((vec![1], 1.0)));
+        assert_eq!(network.find_entanglement_path(1, 3), None); // Node 3 has no links
+        assert_eq!(network.shortest_path(1, 3), None);
+    }
+
+    #[test]
+    fn idle_fidelity_decays_monotonically() {
+        let mut network = entangled_pair(1);
+        network.set_idle_decay_time(Some(Duration::from_millis(100)));
+
+        let mut samples = vec![network.entanglement_fidelity(1, 2).unwrap()];
+        for _ in 0..4 {
+            std::thread::sleep(Duration::from_millis(10));
+            samples.push(network.entanglement_fidelity(1, 2).unwrap());
+        }
+        assert!(samples.windows(2).all(|pair| pair[1] < pair[0]), "{:?}", samples);
+        assert!(samples.iter().all(|&fidelity| fidelity > MIXED_STATE_FIDELITY && fidelity <= 1.0));
+
+        let lease = network.lease_entanglement(1, 2).unwrap(); // Using the pair must not restore fidelity
+        assert!(network.entanglement_fidelity(1, 2).unwrap() <= samples[samples.len() - 1]);
+        assert!(network.release_lease(lease));
+
+        network.set_idle_decay_time(Some(Duration::ZERO));
+        assert_eq!(network.entanglement_fidelity(1, 2), Some(MIXED_STATE_FIDELITY));
+    }
+
+    #[test]
+    fn next_hops_are_cached_along_the_route_until_links_change() {
+        let mut network = QuantumNetwork::line(4);
+
+        assert_eq!(network.next_hop(0, 3), Some(1));
+        for (id, hop) in [(0, 1), (1, 2), (2, 3)] {
+            assert_eq!(network.get_node(id).unwrap().next_hop_cache.get(&3), Some(&hop));
+        }
+
+        // A second lookup is answered from the cache without recomputing the route
+        network.get_node_mut(0).unwrap().next_hop_cache.insert(3, 2);
+        assert_eq!(network.next_hop(0, 3), Some(2));
+
+        network.entangle_nodes(0, 3).unwrap();
+        assert!(network.get_node(0).unwrap().next_hop_cache.is_empty());
+        assert_eq!(network.next_hop(0, 3), Some(3));
+    }
+
+    #[test]
+    fn ring_closes_the_loop() {
+        let network = QuantumNetwork::ring(4);
+        assert_eq!(network.node_count(), 4);
+        assert_eq!(network.link_count(), 4);
+        assert!(network.is_connected());
+        for id in 0..4 {
+            let mut neighbors = network.neighbors(id);
+            neighbors.sort_unstable();
+            let mut expected = vec![(id + 1) % 4, (id + 3) % 4];
+            expected.sort_unstable();
+            assert_eq!(neighbors, expected);
+        }
+
+        assert_eq!(QuantumNetwork::ring(2).link_count(), 1); // Too small to close
+    }
+
+    #[test]
+    fn approx_eq_tolerates_only_small_amplitude_differences() {
+        let state = |theta: f64| {
+            QuantumState::Superposition(Qubit::new(Complex::new(theta.cos(), 0.0), Complex::new(theta.sin(), 0.0)).unwrap())
+        };
+        let epsilon = 1e-9;
+
+        assert!(state(0.5).approx_eq(&state(0.5 + 1e-12), epsilon));
+        assert!(!state(0.5).approx_eq(&state(0.8), epsilon));
+        let entangled = |theta: f64| QuantumState::Entangled(Box::new(state(theta)));
+        assert!(entangled(0.5).approx_eq(&entangled(0.5 + 1e-12), epsilon));
+        assert!(!entangled(0.5).approx_eq(&entangled(0.8), epsilon));
+        assert!(state(0.0).approx_eq(&QuantumState::Zero, epsilon));
+        assert!(!QuantumState::Zero.approx_eq(&QuantumState::One, epsilon));
+        assert!(!QuantumState::Bell(BellState::PhiPlus).approx_eq(&QuantumState::Bell(BellState::PsiMinus), epsilon));
+    }
+
+    #[test]
+    fn tunneling_probability_bounds_are_deterministic() {
+        let mut network = QuantumNetwork::new();
+        network.set_seed(8);
+        network.add_node(1, (0.0, 0.0), QuantumState::Zero);
+        network.add_node(2, (1.0, 0.0), QuantumState::One);
+
+        network.set_tunneling_probability(1.0).unwrap();
+        for _ in 0..100 {
+            assert!(network.quantum_tunneling(1, 2).is_ok());
+        }
+        assert_eq!(network.get_node(1).unwrap().state, QuantumState::One);
+
+        network.get_node_mut(1).unwrap().state = QuantumState::Zero;
+        network.set_tunneling_probability(0.0).unwrap();
+        for _ in 0..100 {
+            assert!(network.quantum_tunneling(1, 2).is_err());
+        }
+        assert_eq!(network.get_node(1).unwrap().state, QuantumState::Zero);
+
+        assert!(network.set_tunneling_probability(1.5).is_err());
+        assert!(network.quantum_tunneling(1, 2).is_err()); // The previous probability is kept
+        assert!(network.quantum_tunneling(1, 9).is_err());
+    }
+
+    #[test]
+    fn forwarding_drops_packets_whose_ttl_runs_out() {
+        let mut network = QuantumNetwork::line(4);
+        let mut packet = QuantumPacket::with_ttl(QuantumPacketType::EncryptedData, 0, 3, vec![1], 3).unwrap();
+
+        assert_eq!(network.forward_packet(0, &mut packet), Some(1));
+        assert_eq!(network.forward_packet(1, &mut packet), Some(2));
+        assert_eq!(network.forward_packet(2, &mut packet), None); // Expired one hop short
+        assert_eq!(packet.ttl, 0);
+
+        let mut packet = QuantumPacket::new(QuantumPacketType::EncryptedData, 0, 3, vec![1]).unwrap();
+        assert_eq!(network.forward_packet(2, &mut packet), Some(3));
+    }
+
+    #[test]
+    fn callbacks_fire_with_the_affected_node_ids() {
+        let entangled = Rc::new(RefCell::new(Vec::new()));
+        let broken = Rc::new(RefCell::new(Vec::new()));
+        let mut network = QuantumNetwork::new();
+        let log = Rc::clone(&entangled);
+        network.on_entangle(Box::new(move |a, b| log.borrow_mut().push((a, b))));
+        let log = Rc::clone(&broken);
+        network.on_break(Box::new(move |id| log.borrow_mut().push(id)));
+        for id in 1..=3 {
+            network.add_node(id, (f64::from(id), 0.0), QuantumState::Zero);
+        }
+
+        network.entangle_nodes(1, 2).unwrap();
+        network.entangle_nodes(3, 2).unwrap();
+        assert_eq!(*entangled.borrow(), vec![(1, 2), (3, 2)]);
+        assert!(broken.borrow().is_empty());
+
+        QuantumEntanglement::break_entanglement(&mut network, 1).unwrap();
+        assert_eq!(*broken.borrow(), vec![1, 2]);
+        assert!(network.entangle_nodes(1, 9).is_err());
+        assert_eq!(entangled.borrow().len(), 2); // Failed attempts do not notify
+    }
+
+    #[test]
+    fn shortest_path_prefers_the_higher_fidelity_branch() {
+        let mut network = QuantumNetwork::new();
+        for (id, position) in [(1, (0.0, 0.0)), (2, (1.0, 1.0)), (3, (1.0, -1.0)), (4, (2.0, -1.0)), (5, (3.0, 0.0))] {
+            network.add_node(id, position, QuantumState::Zero);
+        }
+        // Short branch 1-2-5 over weak links, long branch 1-3-4-5 over strong ones
+        for (a, b, fidelity) in [(1, 2, 0.6), (2, 5, 0.6), (1, 3, 0.95), (3, 4, 0.95), (4, 5, 0.95)] {
+            network.entangle_nodes(a, b).unwrap();
+            network.link_mut(a, b).unwrap().fidelity = fidelity;
+        }
+
+        let (path, fidelity) = network.shortest_path(1, 5).unwrap();
+        assert_eq!(path, vec![1, 3, 4, 5]);
+        assert!((fidelity - 0.95f64.powi(3)).abs() < 1e-9);
+        assert_eq!(network.find_entanglement_path(1, 5), Some(vec![1, 2, 5])); // Fewest hops
+
+        network.link_mut(3, 4).unwrap().fidelity = 0.3;
+        assert_eq!(network.shortest_path(1, 5).unwrap().0, vec![1, 2, 5]);
+    }
+}
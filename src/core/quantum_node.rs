@@ -0,0 +1,281 @@
+// quantum_node.rs - Defines quantum nodes in the network.
+
+// Purpose of this module: 
+// - Represents individual quantum network nodes, shared by the core network,
+//   the simulator and the API.
+// - Tracks each node's position, quantum state, entanglement and keys.
+// - Handles quantum packet transmission and reception.
+
+use crate::core::quantum_packet::{PacketError, QuantumPacket, QuantumPacketType};
+use crate::core::quantum_cryptography::{EncryptionScheme, QuantumCryptography};
+use crate::core::quantum_network::QuantumState;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Reasons a message could not be sent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SendFailure {
+    UnknownReceiver(u32),                             // The receiver is not a known node
+    NoKey(u32),                                       // No key is shared with the receiver
+    KeyExhausted { needed: usize, remaining: usize }, // Too little unused key material is left
+    PayloadTooLarge { size: usize, max: usize },      // The message exceeds the packet payload limit
+}
+
+impl fmt::Display for SendFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SendFailure::UnknownReceiver(id) => write!(f, "Receiver {} is unknown.", id),
+            SendFailure::NoKey(id) => write!(f, "No key is shared with node {}.", id),
+            SendFailure::KeyExhausted { needed, remaining } => {
+                write!(f, "Message needs {} key bytes but only {} remain.", needed, remaining)
+            }
+            SendFailure::PayloadTooLarge { size, max } => {
+                write!(f, "Payload of {} bytes exceeds the limit of {} bytes.", size, max)
+            }
+        }
+    }
+}
+
+/// Tracks how much of a stored one-time-pad key has already been consumed.
+///
+/// Key bytes are used front to back and never reused, so a message that does not
+/// fit in the remaining key material is refused.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyUsage {
+    pub used: usize, // Number of key bytes consumed from the start of the key
+}
+
+/// Represents a quantum node in the network.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuantumNode {
+    pub id: u32,                     // Unique node ID
+    pub position: (f64, f64),        // 2D position of the node in space
+    pub state: QuantumState,         // Quantum state of the node
+    pub entangled_nodes: Vec<u32>,   // List of entangled node IDs
+    pub key_store: HashMap<u32, Vec<u8>>, // Stores quantum keys (per node)
+    pub key_usage: HashMap<u32, KeyUsage>, // Consumed key bytes (per node)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub encryption_scheme: EncryptionScheme, // How messages sent by this node are encrypted
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub next_hop_cache: HashMap<u32, u32>, // Cached next hop towards each destination
+}
+
+impl QuantumNode {
+    /// Creates a new quantum node at the origin in the ground state.
+    ///
+    /// # Arguments
+    /// * `id` - The unique identifier for the node.
+    ///
+    /// # Returns
+    /// * `QuantumNode` - A new quantum node instance.
+    pub fn new(id: u32) -> Self {
+        QuantumNode::new_at(id, (0.0, 0.0))
+    }
+
+    /// Creates a new quantum node at a given position in the ground state.
+    ///
+    /// # Arguments
+    /// * `id` - The unique identifier for the node.
+    /// * `position` - The 2D position of the node.
+    ///
+    /// # Returns
+    /// * `QuantumNode` - A new quantum node instance.
+    pub fn new_at(id: u32, position: (f64, f64)) -> Self {
+        QuantumNode {
+            id,
+            position,
+            state: QuantumState::Zero,
+            entangled_nodes: Vec::new(),
+            key_store: HashMap::new(),
+            key_usage: HashMap::new(),
+            encryption_scheme: EncryptionScheme::default(),
+            next_hop_cache: HashMap::new(),
+        }
+    }
+
+    /// Records quantum entanglement with another node.
+    ///
+    /// # Arguments
+    /// * `peer_id` - The ID of the node to entangle with.
+    ///
+    /// # Returns
+    /// * `true` if the node is now entangled with the peer, `false` if `peer_id` is this node.
+    pub fn entangle_with(&mut self, peer_id: u32) -> bool {
+        if peer_id == self.id {
+            return false;
+        }
+        if !self.entangled_nodes.contains(&peer_id) {
+            self.entangled_nodes.push(peer_id);
+        }
+        true
+    }
+
+    /// Stores the key agreed with an entangled node through Quantum Key Distribution (QKD).
+    ///
+    /// Both parties must store the same key; see `QuantumCryptography::generate_shared_key`.
+    ///
+    /// # Arguments
+    /// * `peer_id` - The ID of the node the key is shared with.
+    /// * `key` - The shared key.
+    ///
+    /// # Returns
+    /// * `true` if the key was successfully stored, `false` if the peer is not entangled.
+    pub fn exchange_keys(&mut self, peer_id: u32, key: &[u8]) -> bool {
+        if peer_id != self.id && self.entangled_nodes.contains(&peer_id) {
+            self.key_store.insert(peer_id, key.to_vec());
+            self.key_usage.insert(peer_id, KeyUsage::default());
+            return true;
+        }
+        false
+    }
+
+    /// Selects how this node encrypts and decrypts messages.
+    ///
+    /// Both ends of a conversation must use the same scheme.
+    ///
+    /// # Arguments
+    /// * `scheme` - The encryption scheme to use.
+    pub fn set_encryption_scheme(&mut self, scheme: EncryptionScheme) {
+        self.encryption_scheme = scheme;
+    }
+
+    /// Sends a quantum data packet to another node.
+    ///
+    /// Under the one-time pad, consumes as many unused key bytes as the message is long;
+    /// under AES-GCM, no key material is consumed. The packet is sealed with a MAC keyed
+    /// by a key derived from the shared key, never by the pad bytes themselves.
+    ///
+    /// # Arguments
+    /// * `receiver_id` - The ID of the destination node.
+    /// * `data` - The plaintext message.
+    ///
+    /// # Returns
+    /// * `Ok(QuantumPacket)` - The encrypted packet.
+    /// * `Err(SendFailure)` - Why the message could not be sent.
+    pub fn send_packet(&mut self, receiver_id: u32, data: &str) -> Result<QuantumPacket, SendFailure> {
+        let key = self.key_store.get(&receiver_id).ok_or(SendFailure::NoKey(receiver_id))?;
+        if self.encryption_scheme == EncryptionScheme::AesGcm {
+            let ciphertext = QuantumCryptography::encrypt_aes_gcm(data, key);
+            let mut packet = QuantumPacket::new(QuantumPacketType::EncryptedData, self.id, receiver_id, ciphertext)
+                .map_err(|PacketError::PayloadTooLarge { size, max }| SendFailure::PayloadTooLarge { size, max })?;
+            packet.seal(&QuantumCryptography::derive_mac_key(key));
+            return Ok(packet);
+        }
+
+        let usage = self.key_usage.entry(receiver_id).or_default();
+        let end = usage.used + data.len();
+        if end > key.len() {
+            // Refuse to reuse key bytes
+            return Err(SendFailure::KeyExhausted {
+                needed: data.len(),
+                remaining: key.len() - usage.used,
+            });
+        }
+
+        let ciphertext = QuantumCryptography::encrypt_otp(data, &key[usage.used..end]).map_err(|_| SendFailure::KeyExhausted {
+            needed: data.len(),
+            remaining: key.len() - usage.used,
+        })?;
+        let mut packet = QuantumPacket::new(QuantumPacketType::EncryptedData, self.id, receiver_id, ciphertext)
+            .map_err(|PacketError::PayloadTooLarge { size, max }| SendFailure::PayloadTooLarge { size, max })?;
+        packet.seal(&QuantumCryptography::derive_mac_key(key));
+        usage.used = end;
+        Ok(packet)
+    }
+
+    /// Receives and decrypts a quantum data packet.
+    ///
+    /// Under the one-time pad, consumes the same key bytes the sender used, assuming
+    /// in-order delivery; AES-GCM packets are additionally authenticated by the cipher.
+    /// Packets whose MAC does not verify are rejected without decryption or key use.
+    ///
+    /// # Arguments
+    /// * `packet` - The incoming encrypted quantum packet.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The decrypted message if successful, `None` if no key is shared
+    ///   with the sender, the MAC does not verify, or the remaining key is too short.
+    pub fn receive_packet(&mut self, packet: &QuantumPacket) -> Option<String> {
+        let key = self.key_store.get(&packet.sender_id)?;
+        if !packet.verify_mac(&QuantumCryptography::derive_mac_key(key)) {
+            return None; // Tampered or forged packet
+        }
+        if self.encryption_scheme == EncryptionScheme::AesGcm {
+            return QuantumCryptography::decrypt_aes_gcm(&packet.payload, key).ok();
+        }
+
+        let usage = self.key_usage.entry(packet.sender_id).or_default();
+        let end = usage.used + packet.payload.len();
+        if end > key.len() {
+            return None;
+        }
+
+        let message = QuantumCryptography::decrypt_otp(&packet.payload, &key[usage.used..end]).ok()?;
+        usage.used = end;
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds node 1 sharing a 16-byte key with node 2, and node 2 sharing it back.
+    fn keyed_pair() -> (QuantumNode, QuantumNode) {
+        let key = QuantumCryptography::generate_shared_key(1, 2);
+        let (mut sender, mut receiver) = (QuantumNode::new(1), QuantumNode::new(2));
+        sender.entangle_with(2);
+        receiver.entangle_with(1);
+        assert!(sender.exchange_keys(2, &key));
+        assert!(receiver.exchange_keys(1, &key));
+        (sender, receiver)
+    }
+
+    #[test]
+    fn send_refuses_to_reuse_key_bytes() {
+        let (mut sender, _) = keyed_pair();
+
+        assert!(sender.send_packet(2, "0123456789").is_ok());
+        assert_eq!(
+            sender.send_packet(2, "abcdefghij").unwrap_err(),
+            SendFailure::KeyExhausted { needed: 10, remaining: 6 }
+        );
+        assert_eq!(sender.key_usage[&2].used, 10); // The failed send consumed nothing
+        assert!(sender.send_packet(2, "abcdef").is_ok());
+        assert!(sender.send_packet(2, "x").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn nodes_round_trip_through_json() {
+        let (mut node, _) = keyed_pair();
+        node.position = (1.5, -2.0);
+        node.state = QuantumState::Entangled(Box::new(QuantumState::One));
+        node.send_packet(2, "abc").unwrap();
+
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(serde_json::from_str::<QuantumNode>(&json).unwrap(), node);
+
+        node.next_hop_cache.insert(5, 2); // Routing caches are not persisted
+        let restored: QuantumNode = serde_json::from_str(&serde_json::to_string(&node).unwrap()).unwrap();
+        assert!(restored.next_hop_cache.is_empty());
+    }
+
+    #[test]
+    fn receive_accepts_intact_packets_and_rejects_tampered_ones() {
+        let (mut sender, mut receiver) = keyed_pair();
+        let key = sender.key_store[&2].clone();
+
+        let packet = sender.send_packet(2, "intact").unwrap();
+        assert!(!packet.verify_mac(&key)); // The MAC key is derived, not the pad itself
+        assert!(packet.verify_mac(&QuantumCryptography::derive_mac_key(&key)));
+        assert_eq!(receiver.receive_packet(&packet).as_deref(), Some("intact"));
+
+        let mut tampered = sender.send_packet(2, "tamper").unwrap();
+        tampered.payload[0] ^= 0x01;
+        assert_eq!(receiver.receive_packet(&tampered), None);
+        assert_eq!(receiver.key_usage[&1].used, 6); // The rejected packet consumed no key bytes
+    }
+}
@@ -0,0 +1,104 @@
+// qubit.rs - Single-qubit states with complex amplitudes.
+
+// Purpose of this module: Represents a qubit as α|0⟩ + β|1⟩ so that superpositions
+// such as (|0⟩ + |1⟩)/√2 can be expressed and measured probabilistically.
+
+use num_complex::Complex;
+use rand::Rng;
+
+/// Tolerance used when checking the normalization invariant.
+pub const NORMALIZATION_TOLERANCE: f64 = 1e-9;
+
+/// A single qubit α|0⟩ + β|1⟩ satisfying |α|² + |β|² = 1.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Qubit {
+    alpha: Complex<f64>, // Amplitude of |0⟩
+    beta: Complex<f64>,  // Amplitude of |1⟩
+}
+
+impl Qubit {
+    /// Creates a qubit from its amplitudes.
+    ///
+    /// # Arguments
+    /// * `alpha` - The amplitude of |0⟩.
+    /// * `beta` - The amplitude of |1⟩.
+    ///
+    /// # Returns
+    /// * `Ok(Qubit)` if the amplitudes are normalized.
+    /// * `Err(String)` if |α|² + |β|² differs from 1.
+    pub fn new(alpha: Complex<f64>, beta: Complex<f64>) -> Result<Self, String> {
+        let norm = alpha.norm_sqr() + beta.norm_sqr();
+        if (norm - 1.0).abs() > NORMALIZATION_TOLERANCE {
+            return Err(format!("Amplitudes are not normalized (|α|² + |β|² = {}).", norm));
+        }
+        Ok(Qubit { alpha, beta })
+    }
+
+    /// Returns the basis state |0⟩.
+    pub fn zero() -> Self {
+        Qubit {
+            alpha: Complex::new(1.0, 0.0),
+            beta: Complex::new(0.0, 0.0),
+        }
+    }
+
+    /// Returns the basis state |1⟩.
+    pub fn one() -> Self {
+        Qubit {
+            alpha: Complex::new(0.0, 0.0),
+            beta: Complex::new(1.0, 0.0),
+        }
+    }
+
+    /// Returns the equal superposition (|0⟩ + |1⟩)/√2.
+    pub fn plus() -> Self {
+        let amplitude = Complex::new(std::f64::consts::FRAC_1_SQRT_2, 0.0);
+        Qubit {
+            alpha: amplitude,
+            beta: amplitude,
+        }
+    }
+
+    /// Returns the amplitude of |0⟩.
+    pub fn alpha(&self) -> Complex<f64> {
+        self.alpha
+    }
+
+    /// Returns the amplitude of |1⟩.
+    pub fn beta(&self) -> Complex<f64> {
+        self.beta
+    }
+
+    /// Returns the probability of measuring |0⟩.
+    pub fn probability_zero(&self) -> f64 {
+        self.alpha.norm_sqr()
+    }
+
+    /// Measures the qubit in the computational basis, collapsing it.
+    ///
+    /// # Arguments
+    /// * `rng` - The source of randomness for the outcome.
+    ///
+    /// # Returns
+    /// * `u8` - The outcome, `0` or `1`.
+    pub fn measure<R: Rng>(&mut self, rng: &mut R) -> u8 {
+        if rng.gen::<f64>() < self.probability_zero() {
+            *self = Qubit::zero();
+            0
+        } else {
+            *self = Qubit::one();
+            1
+        }
+    }
+
+    /// Applies a Pauli-X (bit-flip) gate.
+    pub fn apply_x(&mut self) {
+        std::mem::swap(&mut self.alpha, &mut self.beta);
+    }
+
+    /// Applies a Pauli-Z (phase-flip) gate.
+    pub fn apply_z(&mut self) {
+        self.beta = -self.beta;
+    }
+}
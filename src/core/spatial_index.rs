@@ -0,0 +1,180 @@
+// spatial_index.rs - Grid-based spatial index over node positions.
+
+// Purpose of this module: Speeds up radius queries on node positions so that
+// candidate entanglement partners can be found without scanning every node.
+
+use std::collections::HashMap;
+
+/// Default edge length of a grid cell, in position units.
+pub const DEFAULT_CELL_SIZE: f64 = 10.0;
+
+/// A uniform grid that buckets node IDs by their 2D position.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f64,                                      // Edge length of each square cell
+    cells: HashMap<(i64, i64), Vec<(u32, (f64, f64))>>, // Nodes (ID, position) per cell
+}
+
+impl SpatialGrid {
+    /// Creates an empty spatial grid.
+    ///
+    /// # Arguments
+    /// * `cell_size` - Edge length of each cell. Non-positive or non-finite values
+    ///   fall back to `DEFAULT_CELL_SIZE`.
+    ///
+    /// # Returns
+    /// * `SpatialGrid` - An empty index.
+    pub fn new(cell_size: f64) -> Self {
+        let cell_size = if cell_size.is_finite() && cell_size > 0.0 {
+            cell_size
+        } else {
+            DEFAULT_CELL_SIZE
+        };
+
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Adds a node to the index.
+    ///
+    /// # Arguments
+    /// * `id` - The node ID.
+    /// * `position` - The node's position.
+    pub fn insert(&mut self, id: u32, position: (f64, f64)) {
+        let cell = self.cell_of(position);
+        self.cells.entry(cell).or_default().push((id, position));
+    }
+
+    /// Removes a node from the index.
+    ///
+    /// # Arguments
+    /// * `id` - The node ID.
+    /// * `position` - The position the node was inserted with.
+    ///
+    /// # Returns
+    /// * `true` if the node was found and removed, `false` otherwise.
+    pub fn remove(&mut self, id: u32, position: (f64, f64)) -> bool {
+        let cell = self.cell_of(position);
+        if let Some(entries) = self.cells.get_mut(&cell) {
+            if let Some(index) = entries.iter().position(|(entry_id, _)| *entry_id == id) {
+                entries.swap_remove(index);
+                if entries.is_empty() {
+                    self.cells.remove(&cell);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Finds all nodes within `radius` of `center` (inclusive).
+    ///
+    /// Visits the grid cells the query square overlaps, or every occupied cell when
+    /// that is fewer, so huge or infinite radii stay cheap.
+    ///
+    /// # Arguments
+    /// * `center` - The query position.
+    /// * `radius` - The search radius.
+    ///
+    /// # Returns
+    /// * `Vec<u32>` - IDs of matching nodes in ascending order.
+    pub fn within(&self, center: (f64, f64), radius: f64) -> Vec<u32> {
+        if radius.is_nan() || radius < 0.0 {
+            return Vec::new(); // Negative or NaN radius matches nothing
+        }
+
+        let (min_x, min_y) = self.cell_of((center.0 - radius, center.1 - radius));
+        let (max_x, max_y) = self.cell_of((center.0 + radius, center.1 + radius));
+        let radius_sq = radius * radius;
+        let in_range = |entries: &Vec<(u32, (f64, f64))>| -> Vec<u32> {
+            entries
+                .iter()
+                .filter_map(|(id, position)| {
+                    let (dx, dy) = (position.0 - center.0, position.1 - center.1);
+                    (dx * dx + dy * dy <= radius_sq).then_some(*id)
+                })
+                .collect()
+        };
+
+        let span = (i128::from(max_x) - i128::from(min_x) + 1) * (i128::from(max_y) - i128::from(min_y) + 1);
+        let mut found = Vec::new();
+        if span > self.cells.len() as i128 {
+            for ((x, y), entries) in &self.cells {
+                if (min_x..=max_x).contains(x) && (min_y..=max_y).contains(y) {
+                    found.extend(in_range(entries));
+                }
+            }
+        } else {
+            for x in min_x..=max_x {
+                for y in min_y..=max_y {
+                    if let Some(entries) = self.cells.get(&(x, y)) {
+                        found.extend(in_range(entries));
+                    }
+                }
+            }
+        }
+
+        found.sort_unstable();
+        found
+    }
+
+    /// Maps a position to the coordinates of the cell containing it.
+    fn cell_of(&self, position: (f64, f64)) -> (i64, i64) {
+        (
+            (position.0 / self.cell_size).floor() as i64,
+            (position.1 / self.cell_size).floor() as i64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a grid index over a 20x20 lattice of nodes spaced 3 units apart.
+    fn lattice() -> (SpatialGrid, Vec<(u32, (f64, f64))>) {
+        let mut grid = SpatialGrid::new(DEFAULT_CELL_SIZE);
+        let mut nodes = Vec::new();
+        for i in 0..400u32 {
+            let position = (f64::from(i % 20) * 3.0 - 30.0, f64::from(i / 20) * 3.0 - 30.0);
+            grid.insert(i, position);
+            nodes.push((i, position));
+        }
+        (grid, nodes)
+    }
+
+    #[test]
+    fn within_returns_exactly_the_nodes_inside_the_radius() {
+        let (grid, nodes) = lattice();
+        let (center, radius) = ((1.5, -4.0), 12.5);
+
+        let expected: Vec<u32> = nodes
+            .iter()
+            .filter(|(_, (x, y))| ((x - center.0).powi(2) + (y - center.1).powi(2)).sqrt() <= radius)
+            .map(|(id, _)| *id)
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(grid.within(center, radius), expected);
+    }
+
+    #[test]
+    fn within_handles_unbounded_and_invalid_radii() {
+        let (grid, nodes) = lattice();
+
+        assert_eq!(grid.within((0.0, 0.0), f64::INFINITY).len(), nodes.len());
+        assert_eq!(grid.within((0.0, 0.0), 1e300).len(), nodes.len());
+        assert!(grid.within((0.0, 0.0), -1.0).is_empty());
+        assert!(grid.within((0.0, 0.0), f64::NAN).is_empty());
+    }
+
+    #[test]
+    fn removed_nodes_are_no_longer_found() {
+        let (mut grid, nodes) = lattice();
+        let (id, position) = nodes[0];
+
+        assert!(grid.remove(id, position));
+        assert!(!grid.within(position, 0.0).contains(&id));
+    }
+}
@@ -0,0 +1,180 @@
+// benchmarks.rs - Repeatable performance benchmarks callable from code.
+
+// Purpose of this module:
+// - Measures entanglement throughput, QKD latency and message round-trip time.
+// - Returns structured results with percentiles for comparing configurations.
+
+use crate::core::api::QuantumAPI;
+use std::time::{Duration, Instant};
+
+/// Message sent during the round-trip benchmark (fits in a single 16-byte key).
+const BENCH_MESSAGE: &str = "benchmark";
+
+/// Parameters of a benchmark run.
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    pub node_pairs: usize, // Number of node pairs set up per iteration
+    pub iterations: usize, // Number of times the workload is repeated
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            node_pairs: 100,
+            iterations: 10,
+        }
+    }
+}
+
+/// Latency distribution of a measured operation, in microseconds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub mean_us: f64,
+    pub p50_us: f64,
+    pub p95_us: f64,
+    pub p99_us: f64,
+}
+
+/// Results of a benchmark run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BenchResults {
+    pub entanglement_throughput: f64, // Entanglements established per second
+    pub qkd_latency: LatencySummary,  // Time per key exchange
+    pub round_trip: LatencySummary,   // Time per send + receive of one message
+}
+
+/// Runs the benchmark workload, blocking the calling thread until it finishes.
+///
+/// Drives `run_async` on a private single-threaded Tokio runtime, so it can be called
+/// from plain synchronous code. It panics if called from within an async context; use
+/// `run_async` there instead.
+///
+/// # Arguments
+/// * `config` - The benchmark parameters.
+///
+/// # Returns
+/// * `BenchResults` - Throughput and latency percentiles over all iterations.
+pub fn run(config: &BenchConfig) -> BenchResults {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("failed to build the benchmark runtime")
+        .block_on(run_async(config))
+}
+
+/// Runs the benchmark workload on the caller's Tokio runtime.
+///
+/// Each iteration builds a fresh `QuantumAPI`, then entangles, exchanges keys and
+/// round-trips one message for every node pair.
+///
+/// # Arguments
+/// * `config` - The benchmark parameters.
+///
+/// # Returns
+/// * `BenchResults` - Throughput and latency percentiles over all iterations.
+pub async fn run_async(config: &BenchConfig) -> BenchResults {
+    let mut entangle_total = Duration::ZERO;
+    let mut entangle_count = 0usize;
+    let mut qkd_samples = Vec::new();
+    let mut round_trip_samples = Vec::new();
+
+    for _ in 0..config.iterations {
+        let api = QuantumAPI::new();
+        let pairs: Vec<(u32, u32)> = (0..config.node_pairs as u32).map(|i| (2 * i, 2 * i + 1)).collect();
+        for &(a, b) in &pairs {
+            let _ = api.register_node(a);
+            let _ = api.register_node(b);
+        }
+
+        let start = Instant::now();
+        for &(a, b) in &pairs {
+            if api.entangle_nodes(a, b).is_ok() {
+                entangle_count += 1;
+            }
+        }
+        entangle_total += start.elapsed();
+
+        for &(a, b) in &pairs {
+            let start = Instant::now();
+            let _ = api.exchange_keys(a, b).await;
+            qkd_samples.push(start.elapsed());
+        }
+
+        for &(a, b) in &pairs {
+            let start = Instant::now();
+            if let Ok(packet) = api.send_message(a, b, BENCH_MESSAGE) {
+                let _ = api.receive_message(b, packet);
+            }
+            round_trip_samples.push(start.elapsed());
+        }
+    }
+
+    let entanglement_throughput = if entangle_total.is_zero() {
+        0.0
+    } else {
+        entangle_count as f64 / entangle_total.as_secs_f64()
+    };
+
+    BenchResults {
+        entanglement_throughput,
+        qkd_latency: summarize(qkd_samples),
+        round_trip: summarize(round_trip_samples),
+    }
+}
+
+/// Computes the mean and nearest-rank percentiles of a set of durations.
+fn summarize(mut samples: Vec<Duration>) -> LatencySummary {
+    if samples.is_empty() {
+        return LatencySummary::default();
+    }
+
+    samples.sort_unstable();
+    let micros = |d: Duration| d.as_secs_f64() * 1e6;
+    let percentile = |p: f64| {
+        let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+        micros(samples[rank.clamp(1, samples.len()) - 1])
+    };
+
+    LatencySummary {
+        samples: samples.len(),
+        mean_us: samples.iter().map(|&d| micros(d)).sum::<f64>() / samples.len() as f64,
+        p50_us: percentile(50.0),
+        p95_us: percentile(95.0),
+        p99_us: percentile(99.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiny_benchmark_reports_every_metric() {
+        let config = BenchConfig {
+            node_pairs: 3,
+            iterations: 2,
+        };
+
+        let results = run(&config);
+        assert!(results.entanglement_throughput > 0.0);
+        for summary in [&results.qkd_latency, &results.round_trip] {
+            assert_eq!(summary.samples, 6);
+            assert!(summary.mean_us.is_finite() && summary.mean_us >= 0.0);
+            assert!(summary.p50_us <= summary.p95_us && summary.p95_us <= summary.p99_us);
+        }
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let samples = (1..=100).map(Duration::from_micros).collect();
+
+        let summary = summarize(samples);
+        assert_eq!(summary.samples, 100);
+        assert!((summary.mean_us - 50.5).abs() < 1e-6);
+        assert!((summary.p50_us - 50.0).abs() < 1e-6);
+        assert!((summary.p95_us - 95.0).abs() < 1e-6);
+        assert!((summary.p99_us - 99.0).abs() < 1e-6);
+        assert_eq!(summarize(Vec::new()), LatencySummary::default());
+    }
+}
@@ -6,9 +6,29 @@
 // - Ensures integrity using quantum cryptographic techniques.
 
 use crate::core::quantum_cryptography::QuantumCryptography;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 
-/// Represents different types of quantum packets.
+/// Length in bytes of a packet MAC (HMAC-SHA256).
+pub const MAC_LENGTH: usize = 32;
+
+/// Default application-level limit on packet payload size, in bytes.
+///
+/// This is independent of any link MTU and exists to catch oversized payloads early.
+pub const MAX_PAYLOAD: usize = 64 * 1024;
+
+/// Hop limit given to packets created without an explicit TTL.
+pub const DEFAULT_TTL: u8 = 16;
+
+/// Errors that can occur while constructing a quantum packet.
 #[derive(Debug, Clone, PartialEq)]
+pub enum PacketError {
+    PayloadTooLarge { size: usize, max: usize }, // Payload exceeds the configured limit
+}
+
+/// Represents different types of quantum packets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum QuantumPacketType {
     Entanglement,   // Used for quantum entanglement distribution
     KeyExchange,    // Used for quantum key distribution (QKD)
@@ -17,12 +37,31 @@ pub enum QuantumPacketType {
 }
 
 /// Struct representing a quantum packet.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuantumPacket {
     pub packet_type: QuantumPacketType, // Type of quantum packet
     pub sender_id: u32,   // ID of the sending quantum node
     pub receiver_id: u32, // ID of the receiving quantum node
-    pub payload: Vec<u8>, // Encoded quantum data
+    #[serde(with = "base64_payload")]
+    pub payload: Vec<u8>, // Encoded quantum data (base64 in JSON)
+    #[serde(with = "base64_mac")]
+    pub mac: [u8; MAC_LENGTH], // HMAC-SHA256 over the payload; all zeros until sealed
+    #[serde(default)]
+    pub fragment_index: u32, // Position of this fragment, starting at 0
+    #[serde(default = "single_fragment")]
+    pub fragment_total: u32, // Number of fragments the original payload was split into
+    #[serde(default = "default_ttl")]
+    pub ttl: u8, // Remaining hops before the packet is dropped
+}
+
+/// Fragment count of an unfragmented packet, used when the field is absent from JSON.
+fn single_fragment() -> u32 {
+    1
+}
+
+/// TTL of a packet whose JSON omits the field.
+fn default_ttl() -> u8 {
+    DEFAULT_TTL
 }
 
 impl QuantumPacket {
@@ -35,14 +74,176 @@ impl QuantumPacket {
     /// * `payload` - The data to be transmitted.
     ///
     /// # Returns
-    /// * `QuantumPacket` - A new quantum data packet.
-    pub fn new(packet_type: QuantumPacketType, sender_id: u32, receiver_id: u32, payload: Vec<u8>) -> Self {
-        QuantumPacket {
+    /// * `Ok(QuantumPacket)` - A new quantum data packet.
+    /// * `Err(PacketError::PayloadTooLarge)` if the payload exceeds `MAX_PAYLOAD`.
+    pub fn new(packet_type: QuantumPacketType, sender_id: u32, receiver_id: u32, payload: Vec<u8>) -> Result<Self, PacketError> {
+        QuantumPacket::with_max_payload(packet_type, sender_id, receiver_id, payload, MAX_PAYLOAD)
+    }
+
+    /// Creates a new quantum packet with a custom hop limit.
+    ///
+    /// # Arguments
+    /// * `packet_type` - The type of the quantum packet.
+    /// * `sender_id` - The sender node ID.
+    /// * `receiver_id` - The receiver node ID.
+    /// * `payload` - The data to be transmitted.
+    /// * `ttl` - The number of hops the packet may take before it is dropped.
+    ///
+    /// # Returns
+    /// * `Ok(QuantumPacket)` - A new quantum data packet.
+    /// * `Err(PacketError::PayloadTooLarge)` if the payload exceeds `MAX_PAYLOAD`.
+    pub fn with_ttl(packet_type: QuantumPacketType, sender_id: u32, receiver_id: u32, payload: Vec<u8>, ttl: u8) -> Result<Self, PacketError> {
+        let mut packet = QuantumPacket::new(packet_type, sender_id, receiver_id, payload)?;
+        packet.ttl = ttl;
+        Ok(packet)
+    }
+
+    /// Creates a new quantum packet with a custom payload size limit.
+    ///
+    /// # Arguments
+    /// * `packet_type` - The type of the quantum packet.
+    /// * `sender_id` - The sender node ID.
+    /// * `receiver_id` - The receiver node ID.
+    /// * `payload` - The data to be transmitted.
+    /// * `max_payload` - The maximum allowed payload size in bytes.
+    ///
+    /// # Returns
+    /// * `Ok(QuantumPacket)` - A new quantum data packet.
+    /// * `Err(PacketError::PayloadTooLarge)` if the payload exceeds `max_payload`.
+    pub fn with_max_payload(
+        packet_type: QuantumPacketType,
+        sender_id: u32,
+        receiver_id: u32,
+        payload: Vec<u8>,
+        max_payload: usize,
+    ) -> Result<Self, PacketError> {
+        if payload.len() > max_payload {
+            return Err(PacketError::PayloadTooLarge {
+                size: payload.len(),
+                max: max_payload,
+            });
+        }
+
+        Ok(QuantumPacket {
             packet_type,
             sender_id,
             receiver_id,
             payload,
+            mac: [0; MAC_LENGTH],
+            fragment_index: 0,
+            fragment_total: 1,
+            ttl: DEFAULT_TTL,
+        })
+    }
+
+    /// Consumes one hop of the packet's TTL.
+    ///
+    /// # Returns
+    /// * `true` if the packet may still be forwarded.
+    /// * `false` if the TTL has reached zero and the packet must be dropped.
+    pub fn decrement_ttl(&mut self) -> bool {
+        self.ttl = self.ttl.saturating_sub(1);
+        self.ttl > 0
+    }
+
+    /// Splits the packet into fragments whose payloads are at most `max_size` bytes.
+    ///
+    /// Every fragment keeps the original MAC, which covers the full payload and is
+    /// checked after reassembly.
+    ///
+    /// # Arguments
+    /// * `max_size` - The maximum payload size of each fragment (at least one byte).
+    ///
+    /// # Returns
+    /// * `Vec<QuantumPacket>` - The fragments in order; a single fragment if the payload already fits.
+    pub fn fragment(&self, max_size: usize) -> Vec<QuantumPacket> {
+        if self.payload.is_empty() {
+            return vec![self.clone()];
+        }
+
+        let chunks: Vec<&[u8]> = self.payload.chunks(max_size.max(1)).collect();
+        let total = chunks.len() as u32;
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| QuantumPacket {
+                packet_type: self.packet_type.clone(),
+                sender_id: self.sender_id,
+                receiver_id: self.receiver_id,
+                payload: chunk.to_vec(),
+                mac: self.mac,
+                fragment_index: index as u32,
+                fragment_total: total,
+                ttl: self.ttl,
+            })
+            .collect()
+    }
+
+    /// Rejoins fragments produced by `fragment` into the original packet.
+    ///
+    /// The fragments must be complete, in order, and belong to the same packet.
+    /// On success the fragments are drained from `fragments`; otherwise it is left untouched.
+    ///
+    /// # Arguments
+    /// * `fragments` - The received fragments.
+    ///
+    /// # Returns
+    /// * `Some(QuantumPacket)` - The reassembled packet.
+    /// * `None` - If a fragment is missing, out of order, or from a different packet.
+    pub fn reassemble(fragments: &mut Vec<QuantumPacket>) -> Option<QuantumPacket> {
+        let first = fragments.first()?;
+        let complete = fragments.len() == first.fragment_total as usize
+            && fragments.iter().enumerate().all(|(index, fragment)| {
+                fragment.fragment_index as usize == index
+                    && fragment.fragment_total == first.fragment_total
+                    && fragment.packet_type == first.packet_type
+                    && fragment.sender_id == first.sender_id
+                    && fragment.receiver_id == first.receiver_id
+                    && fragment.mac == first.mac
+            });
+        if !complete {
+            return None;
         }
+
+        let mut packet = first.clone();
+        packet.payload = fragments.drain(..).flat_map(|fragment| fragment.payload).collect();
+        packet.fragment_index = 0;
+        packet.fragment_total = 1;
+        Some(packet)
+    }
+
+    /// Computes the HMAC-SHA256 of the payload under a MAC key.
+    ///
+    /// # Arguments
+    /// * `key` - The MAC key, derived with `QuantumCryptography::derive_mac_key`.
+    ///
+    /// # Returns
+    /// * `[u8; MAC_LENGTH]` - The payload MAC.
+    pub fn compute_mac(&self, key: &[u8]) -> [u8; MAC_LENGTH] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(&self.payload);
+        mac.finalize().into_bytes().into()
+    }
+
+    /// Sets the packet MAC from the current payload.
+    ///
+    /// # Arguments
+    /// * `key` - The MAC key, derived with `QuantumCryptography::derive_mac_key`.
+    pub fn seal(&mut self, key: &[u8]) {
+        self.mac = self.compute_mac(key);
+    }
+
+    /// Verifies the packet MAC in constant time.
+    ///
+    /// # Arguments
+    /// * `key` - The MAC key, derived with `QuantumCryptography::derive_mac_key`.
+    ///
+    /// # Returns
+    /// * `true` if the payload is authentic under `key`, `false` if it was tampered with.
+    pub fn verify_mac(&self, key: &[u8]) -> bool {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(&self.payload);
+        mac.verify_slice(&self.mac).is_ok()
     }
 
     /// Encrypts the quantum packet using a quantum-secure key.
@@ -53,12 +254,16 @@ impl QuantumPacket {
     /// # Returns
     /// * `QuantumPacket` - The encrypted quantum packet.
     pub fn encrypt(&self, key: &Vec<u8>) -> QuantumPacket {
-        let encrypted_payload = QuantumCryptography::encrypt(&String::from_utf8_lossy(&self.payload), key);
+        let encrypted_payload = QuantumCryptography::encrypt_stream(&String::from_utf8_lossy(&self.payload), key);
         QuantumPacket {
             packet_type: self.packet_type.clone(),
             sender_id: self.sender_id,
             receiver_id: self.receiver_id,
             payload: encrypted_payload,
+            mac: [0; MAC_LENGTH], // The MAC covered the old payload
+            fragment_index: self.fragment_index,
+            fragment_total: self.fragment_total,
+            ttl: self.ttl,
         }
     }
 
@@ -70,12 +275,167 @@ impl QuantumPacket {
     /// # Returns
     /// * `QuantumPacket` - The decrypted quantum packet.
     pub fn decrypt(&self, key: &Vec<u8>) -> QuantumPacket {
-        let decrypted_payload = QuantumCryptography::decrypt(&self.payload, key);
+        let decrypted_payload = QuantumCryptography::decrypt_stream(&self.payload, key);
         QuantumPacket {
             packet_type: self.packet_type.clone(),
             sender_id: self.sender_id,
             receiver_id: self.receiver_id,
             payload: decrypted_payload.into_bytes(),
+            mac: [0; MAC_LENGTH], // The MAC covered the old payload
+            fragment_index: self.fragment_index,
+            fragment_total: self.fragment_total,
+            ttl: self.ttl,
         }
     }
 }
+
+/// Serializes packet payloads as standard base64 strings instead of byte arrays.
+mod base64_payload {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(payload: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(payload))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(de::Error::custom)
+    }
+}
+
+/// Serializes packet MACs as standard base64 strings.
+mod base64_mac {
+    use super::MAC_LENGTH;
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(mac: &[u8; MAC_LENGTH], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(mac))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; MAC_LENGTH], D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        let bytes = STANDARD.decode(encoded).map_err(de::Error::custom)?;
+        bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| de::Error::invalid_length(bytes.len(), &"a 32-byte MAC"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_rejects_oversized_payload() {
+        let result = QuantumPacket::new(QuantumPacketType::EncryptedData, 1, 2, vec![0; MAX_PAYLOAD + 1]);
+        assert_eq!(
+            result.unwrap_err(),
+            PacketError::PayloadTooLarge {
+                size: MAX_PAYLOAD + 1,
+                max: MAX_PAYLOAD,
+            }
+        );
+    }
+
+    #[test]
+    fn new_accepts_payload_at_limit() {
+        assert!(QuantumPacket::new(QuantumPacketType::EncryptedData, 1, 2, vec![0; MAX_PAYLOAD]).is_ok());
+    }
+
+    #[test]
+    fn with_max_payload_applies_custom_limit() {
+        let result = QuantumPacket::with_max_payload(QuantumPacketType::KeyExchange, 1, 2, vec![0; 9], 8);
+        assert_eq!(result.unwrap_err(), PacketError::PayloadTooLarge { size: 9, max: 8 });
+    }
+
+    /// Builds a packet whose `len`-byte payload follows a recognizable pattern.
+    fn packet_with_payload(len: usize) -> QuantumPacket {
+        let payload = (0..len).map(|i| (i % 251) as u8).collect();
+        QuantumPacket::new(QuantumPacketType::EncryptedData, 1, 2, payload).unwrap()
+    }
+
+    #[test]
+    fn fragments_reassemble_into_the_original_payload() {
+        let packet = packet_with_payload(1000);
+
+        let mut fragments = packet.fragment(256);
+        assert_eq!(fragments.len(), 4);
+        assert!(fragments.iter().all(|fragment| fragment.payload.len() <= 256));
+
+        let reassembled = QuantumPacket::reassemble(&mut fragments).unwrap();
+        assert_eq!(reassembled.payload, packet.payload);
+        assert_eq!(reassembled.fragment_total, 1);
+        assert!(fragments.is_empty());
+    }
+
+    #[test]
+    fn reassemble_rejects_out_of_order_fragments() {
+        let mut fragments = packet_with_payload(1000).fragment(256);
+        fragments.swap(1, 2);
+
+        assert!(QuantumPacket::reassemble(&mut fragments).is_none());
+        assert_eq!(fragments.len(), 4); // Left untouched on failure
+    }
+
+    #[test]
+    fn reassemble_rejects_missing_fragments() {
+        let mut fragments = packet_with_payload(1000).fragment(256);
+        fragments.remove(3);
+
+        assert!(QuantumPacket::reassemble(&mut fragments).is_none());
+    }
+
+    #[test]
+    fn packets_round_trip_through_json_with_base64_bytes() {
+        let mut packet = QuantumPacket::new(QuantumPacketType::EncryptedData, 1, 2, b"hello".to_vec()).unwrap();
+        packet.seal(b"key");
+
+        let json: serde_json::Value = serde_json::to_value(&packet).unwrap();
+        assert_eq!(json["packet_type"], "EncryptedData");
+        assert_eq!(json["payload"], "aGVsbG8=");
+        assert!(json["mac"].is_string());
+
+        let restored: QuantumPacket = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.packet_type, packet.packet_type);
+        assert_eq!((restored.sender_id, restored.receiver_id), (1, 2));
+        assert_eq!(restored.payload, packet.payload);
+        assert_eq!(restored.mac, packet.mac);
+        assert_eq!(restored.ttl, DEFAULT_TTL);
+        assert!(restored.verify_mac(b"key"));
+    }
+
+    #[test]
+    fn packet_json_rejects_invalid_base64_and_mac_lengths() {
+        let packet = serde_json::json!({
+            "packet_type": "EncryptedData",
+            "sender_id": 1,
+            "receiver_id": 2,
+            "payload": "not base64!",
+            "mac": "",
+        });
+        assert!(serde_json::from_value::<QuantumPacket>(packet).is_err());
+
+        let packet = serde_json::json!({
+            "packet_type": "EncryptedData",
+            "sender_id": 1,
+            "receiver_id": 2,
+            "payload": "aGVsbG8=",
+            "mac": "AAAA",
+        });
+        assert!(serde_json::from_value::<QuantumPacket>(packet).is_err());
+    }
+
+    #[test]
+    fn ttl_expires_after_the_last_hop() {
+        let mut packet = QuantumPacket::with_ttl(QuantumPacketType::EncryptedData, 1, 2, vec![1], 2).unwrap();
+        assert!(packet.decrement_ttl());
+        assert!(!packet.decrement_ttl()); // Down to zero: expired
+        assert!(!packet.decrement_ttl());
+        assert_eq!(packet.ttl, 0);
+
+        let packet = QuantumPacket::new(QuantumPacketType::EncryptedData, 1, 2, vec![1]).unwrap();
+        assert_eq!(packet.ttl, DEFAULT_TTL);
+    }
+}
@@ -5,14 +5,108 @@
 // - Simulates entanglement, cryptography, and error correction
 // - Provides a testing environment for quantum communication
 
-use crate::core::quantum_network::{QuantumNetwork, QuantumNode};
+use crate::core::quantum_network::{EntanglementLink, QuantumNetwork, QuantumState};
+use crate::core::quantum_node::QuantumNode;
+use crate::core::quantum_packet::QuantumPacket;
 use crate::core::quantum_entanglement::QuantumEntanglement;
 use crate::core::quantum_cryptography::QuantumCryptography;
-use crate::core::quantum_error_correction::QuantumErrorCorrection;
+use crate::core::quantum_error_correction::{NoiseModel, QuantumError, QuantumErrorCorrection};
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Fraction of link fidelity (above the mixed-state floor) lost each time a node suffers an error.
+pub const ERROR_FIDELITY_LOSS: f64 = 0.1;
+
+/// Default signal propagation speed, in position units per unit of simulated time.
+pub const DEFAULT_SIGNAL_SPEED: f64 = 1.0;
+
+/// Number of equal-width QBER histogram bins covering [0, 0.5].
+pub const QBER_HISTOGRAM_BINS: usize = 10;
+
+/// Largest QBER tracked by the histogram; higher values fall into the last bin.
+const QBER_HISTOGRAM_MAX: f64 = 0.5;
+
+/// Counters aggregated over a simulation run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimMetrics {
+    pub messages_sent: u64,                         // Messages encrypted and sent
+    pub messages_decrypted_ok: u64,                 // Messages received and decrypted successfully
+    pub errors_introduced: u64,                     // Errors applied by the noise model
+    pub errors_corrected: u64,                      // Errors detected and corrected
+    pub qber_histogram: [u64; QBER_HISTOGRAM_BINS], // Expected QBER of each key exchange
+}
+
+impl SimMetrics {
+    /// Adds a QBER observation to the histogram.
+    ///
+    /// # Arguments
+    /// * `qber` - The observed quantum bit error rate; values are clamped to [0, 0.5].
+    pub fn record_qber(&mut self, qber: f64) {
+        let fraction = (qber / QBER_HISTOGRAM_MAX).clamp(0.0, 1.0);
+        let bin = ((fraction * QBER_HISTOGRAM_BINS as f64) as usize).min(QBER_HISTOGRAM_BINS - 1);
+        self.qber_histogram[bin] += 1;
+    }
+
+    /// Returns the fraction of sent messages that were decrypted successfully.
+    ///
+    /// # Returns
+    /// * `Some(f64)` - `messages_decrypted_ok / messages_sent`.
+    /// * `None` - If no messages have been sent.
+    pub fn reliability(&self) -> Option<f64> {
+        if self.messages_sent == 0 {
+            None
+        } else {
+            Some(self.messages_decrypted_ok as f64 / self.messages_sent as f64)
+        }
+    }
+}
+
+/// A recorded simulator operation, stamped with the simulated time it occurred at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimEvent {
+    NodeAdded { time: f64, node_id: u32 },
+    Entangled { time: f64, node_id_1: u32, node_id_2: u32 },
+    KeyExchanged { time: f64, node_id_1: u32, node_id_2: u32 },
+    ErrorIntroduced { time: f64, node_id: u32, error: QuantumError },
+    ErrorCorrected { time: f64, node_id: u32 },
+    MessageSent { time: f64, sender: u32, receiver: u32 },
+}
+
+/// A single step of a declarative simulation scenario, executed by `run_scenario`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SimCommand {
+    AddNode(u32),                             // Add a node at the origin
+    Entangle(u32, u32),                       // Entangle two nodes
+    Qkd(u32, u32),                            // Run QKD between two nodes
+    Send { from: u32, to: u32, msg: String }, // Send an encrypted message and deliver it to the receiver
+    InjectError(u32),                         // Sample an error from the noise model into a node
+    Correct(u32),                             // Detect and correct errors on a node
+    Tick(f64),                                // Advance the simulation clock
+}
+
+/// The outcome of one `SimCommand`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SimResult {
+    Ok,                       // The command completed with nothing further to report
+    Failed,                   // The command could not be carried out
+    Key(Vec<u8>),             // The key produced by `Qkd`
+    Delivered { delay: f64 }, // The propagation delay of a message sent and decrypted by `Send`
+    Error(QuantumError),      // The error introduced by `InjectError`
+}
 
 /// Represents the main quantum network simulator.
 pub struct QuantumSimulator {
     network: QuantumNetwork,
+    partition_groups: Option<HashMap<u32, usize>>, // Group index per node while partitioned
+    suspended_links: Vec<EntanglementLink>,        // Cross-group link records held back until `heal`
+    clock: f64,                                    // Simulated time elapsed
+    decoherence_rate: f64,                         // Decoherence events per node per unit of time
+    noise_model: NoiseModel,                       // Error distribution used by `introduce_errors`
+    signal_speed: f64,                             // Position units travelled per unit of simulated time
+    events: Vec<SimEvent>,                         // History of operations, oldest first
+    metrics: SimMetrics,                           // Counters aggregated over the run
 }
 
 impl QuantumSimulator {
@@ -20,6 +114,99 @@ impl QuantumSimulator {
     pub fn new() -> Self {
         QuantumSimulator {
             network: QuantumNetwork::new(),
+            partition_groups: None,
+            suspended_links: Vec::new(),
+            clock: 0.0,
+            decoherence_rate: 0.0,
+            noise_model: NoiseModel::default(),
+            signal_speed: DEFAULT_SIGNAL_SPEED,
+            events: Vec::new(),
+            metrics: SimMetrics::default(),
+        }
+    }
+
+    /// Creates a simulator whose random outcomes are reproducible.
+    ///
+    /// Two simulators created with the same seed and given the same sequence of
+    /// operations produce identical keys, errors and decoherence events.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed for the simulator's random number generator.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut simulator = QuantumSimulator::new();
+        simulator.network.set_seed(seed);
+        simulator
+    }
+
+    /// Sets the signal speed used to compute propagation delays.
+    ///
+    /// # Arguments
+    /// * `speed` - Position units travelled per unit of simulated time. Non-positive or
+    ///   non-finite values fall back to `DEFAULT_SIGNAL_SPEED`.
+    pub fn set_signal_speed(&mut self, speed: f64) {
+        self.signal_speed = if speed.is_finite() && speed > 0.0 { speed } else { DEFAULT_SIGNAL_SPEED };
+    }
+
+    /// Sets the noise model used by `introduce_errors`.
+    ///
+    /// # Arguments
+    /// * `model` - The error distribution to sample from.
+    pub fn set_noise_model(&mut self, model: NoiseModel) {
+        self.noise_model = model;
+    }
+
+    /// Sets the decoherence rate used by `tick`.
+    ///
+    /// # Arguments
+    /// * `rate` - Expected decoherence events per node per unit of simulated time
+    ///   (negative values are treated as zero).
+    pub fn set_decoherence_rate(&mut self, rate: f64) {
+        self.decoherence_rate = rate.max(0.0);
+    }
+
+    /// Returns the recorded history of operations, oldest first.
+    pub fn events(&self) -> &[SimEvent] {
+        &self.events
+    }
+
+    /// Discards the recorded history of operations.
+    pub fn clear_events(&mut self) {
+        self.events.clear();
+    }
+
+    /// Returns the counters aggregated over the run so far.
+    pub fn metrics(&self) -> &SimMetrics {
+        &self.metrics
+    }
+
+    /// Returns the current simulated time.
+    pub fn clock(&self) -> f64 {
+        self.clock
+    }
+
+    /// Advances the simulation clock and applies decoherence.
+    ///
+    /// Each node decoheres with probability `decoherence_rate * dt` (capped at 1).
+    /// A decohering node loses all of its entanglement if it has any, and otherwise
+    /// suffers a depolarizing error. Links suspended by a partition are lost as well.
+    ///
+    /// # Arguments
+    /// * `dt` - The amount of simulated time to advance.
+    pub fn tick(&mut self, dt: f64) {
+        self.clock += dt;
+
+        let probability = (self.decoherence_rate * dt).clamp(0.0, 1.0);
+        for node_id in self.network.node_ids() {
+            if self.network.rng().gen::<f64>() >= probability {
+                continue;
+            }
+
+            self.suspended_links.retain(|link| link.a != node_id && link.b != node_id);
+            if QuantumEntanglement::break_entanglement(&mut self.network, node_id).is_err() {
+                if let Some(node) = self.network.get_node_mut(node_id) {
+                    QuantumErrorCorrection::apply_error(node, &QuantumError::Depolarizing);
+                }
+            }
         }
     }
 
@@ -28,7 +215,17 @@ impl QuantumSimulator {
     /// # Arguments
     /// * `node_id` - The ID of the new quantum node.
     pub fn add_node(&mut self, node_id: u32) {
-        self.network.add_node(QuantumNode::new(node_id));
+        self.add_node_at(node_id, (0.0, 0.0));
+    }
+
+    /// Adds a quantum node to the simulation at a given position.
+    ///
+    /// # Arguments
+    /// * `node_id` - The ID of the new quantum node.
+    /// * `position` - The 2D position of the node.
+    pub fn add_node_at(&mut self, node_id: u32, position: (f64, f64)) {
+        self.network.add_node(node_id, position, QuantumState::Zero);
+        self.events.push(SimEvent::NodeAdded { time: self.clock, node_id });
     }
 
     /// Establishes quantum entanglement between two nodes.
@@ -39,13 +236,81 @@ impl QuantumSimulator {
     ///
     /// # Returns
     /// * `true` if entanglement was successfully established.
-    /// * `false` if the operation failed.
+    /// * `false` if the operation failed or the nodes are in different partitions.
     pub fn entangle_nodes(&mut self, node_id_1: u32, node_id_2: u32) -> bool {
-        QuantumEntanglement::entangle(&mut self.network, node_id_1, node_id_2)
+        if self.is_partitioned_pair(node_id_1, node_id_2) {
+            return false;
+        }
+        if QuantumEntanglement::entangle_nodes(&mut self.network, node_id_1, node_id_2).is_err() {
+            return false;
+        }
+        self.events.push(SimEvent::Entangled { time: self.clock, node_id_1, node_id_2 });
+        true
+    }
+
+    /// Finds a chain of entangled nodes between two nodes.
+    ///
+    /// # Arguments
+    /// * `src` - The ID of the starting node.
+    /// * `dst` - The ID of the destination node.
+    ///
+    /// # Returns
+    /// * `Some(Vec<u32>)` - The node IDs along the path, from `src` to `dst`.
+    ///   A route from a node to itself is `[src]`.
+    /// * `None` - If either node is missing or no entanglement path exists (e.g. across a partition).
+    pub fn find_entanglement_path(&self, src: u32, dst: u32) -> Option<Vec<u32>> {
+        self.network.find_entanglement_path(src, dst)
+    }
+
+    /// Splits the network into isolated groups.
+    ///
+    /// Entanglement links between different groups are suspended, not destroyed: their
+    /// records leave the network, so no operation treats the pair as entangled, and new
+    /// cross-group entanglement is refused until `heal` is called. Nodes not listed in any
+    /// group together form one additional group. An existing partition is healed first.
+    ///
+    /// # Arguments
+    /// * `groups` - The node IDs of each group.
+    pub fn partition(&mut self, groups: &[Vec<u32>]) {
+        self.heal();
+
+        let group_of: HashMap<u32, usize> = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(index, group)| group.iter().map(move |&id| (id, index)))
+            .collect();
+
+        for (node_id_1, node_id_2) in self.network.linked_pairs() {
+            if group_of.get(&node_id_1) != group_of.get(&node_id_2) {
+                if let Some(link) = self.network.suspend_link(node_id_1, node_id_2) {
+                    self.suspended_links.push(link);
+                }
+            }
+        }
+
+        self.partition_groups = Some(group_of);
+    }
+
+    /// Ends a partition, restoring all suspended cross-group links that survived it.
+    pub fn heal(&mut self) {
+        for link in self.suspended_links.drain(..) {
+            self.network.restore_link(link);
+        }
+        self.partition_groups = None;
+    }
+
+    /// Checks whether two nodes are separated by the current partition.
+    fn is_partitioned_pair(&self, node_id_1: u32, node_id_2: u32) -> bool {
+        self.partition_groups
+            .as_ref()
+            .map_or(false, |groups| groups.get(&node_id_1) != groups.get(&node_id_2))
     }
 
     /// Performs quantum key distribution (QKD) between two nodes.
     ///
+    /// On success, the expected QBER of the consumed pair, 2(1 - F)/3 for link
+    /// fidelity F, is added to the metrics histogram.
+    ///
     /// # Arguments
     /// * `node_id_1` - The ID of the first node.
     /// * `node_id_2` - The ID of the second node.
@@ -54,10 +319,13 @@ impl QuantumSimulator {
     /// * `Some(Vec<u8>)` - The generated quantum key if successful.
     /// * `None` - If QKD fails.
     pub fn perform_qkd(&mut self, node_id_1: u32, node_id_2: u32) -> Option<Vec<u8>> {
-        match QuantumCryptography::quantum_key_distribution(&mut self.network, node_id_1, node_id_2) {
-            Ok(key) => Some(key),
-            Err(_) => None,
+        let fidelity = self.network.entanglement_fidelity(node_id_1, node_id_2);
+        let key = QuantumCryptography::quantum_key_distribution(&mut self.network, node_id_1, node_id_2).ok()?;
+        if let Some(fidelity) = fidelity {
+            self.metrics.record_qber(2.0 * (1.0 - fidelity) / 3.0);
         }
+        self.events.push(SimEvent::KeyExchanged { time: self.clock, node_id_1, node_id_2 });
+        Some(key)
     }
 
     /// Encrypts and transmits a message securely.
@@ -69,7 +337,43 @@ impl QuantumSimulator {
     /// # Returns
     /// * `Vec<u8>` - The encrypted message.
     pub fn secure_transmit(&self, message: &str, key: &Vec<u8>) -> Vec<u8> {
-        QuantumCryptography::encrypt(message, key)
+        QuantumCryptography::encrypt_stream(message, key)
+    }
+
+    /// Sends a message from one node to another and computes its propagation delay.
+    ///
+    /// The sender must already share a key with the receiver. The delay is the
+    /// Euclidean distance between the nodes divided by the signal speed.
+    ///
+    /// # Arguments
+    /// * `sender` - The ID of the sending node.
+    /// * `receiver` - The ID of the receiving node.
+    /// * `message` - The plaintext message.
+    ///
+    /// # Returns
+    /// * `Some((QuantumPacket, f64))` - The encrypted packet and its delay in simulated time.
+    /// * `None` - If either node is missing or the message cannot be sent.
+    pub fn transmit_with_delay(&mut self, sender: u32, receiver: u32, message: &str) -> Option<(QuantumPacket, f64)> {
+        let delay = self.network.distance(sender, receiver)? / self.signal_speed;
+        let packet = self.network.get_node_mut(sender)?.send_packet(receiver, message).ok()?;
+        self.events.push(SimEvent::MessageSent { time: self.clock, sender, receiver });
+        self.metrics.messages_sent += 1;
+        Some((packet, delay))
+    }
+
+    /// Delivers a packet to its receiving node, which verifies and decrypts it.
+    ///
+    /// # Arguments
+    /// * `receiver` - The ID of the receiving node.
+    /// * `packet` - The packet produced by `transmit_with_delay`.
+    ///
+    /// # Returns
+    /// * `Some(String)` - The decrypted message.
+    /// * `None` - If the receiver is missing or the packet cannot be verified or decrypted.
+    pub fn deliver_packet(&mut self, receiver: u32, packet: &QuantumPacket) -> Option<String> {
+        let message = self.network.get_node_mut(receiver)?.receive_packet(packet)?;
+        self.metrics.messages_decrypted_ok += 1;
+        Some(message)
     }
 
     /// Receives and decrypts a quantum-secure message.
@@ -81,22 +385,70 @@ impl QuantumSimulator {
     /// # Returns
     /// * `String` - The decrypted message.
     pub fn secure_receive(&self, ciphertext: &Vec<u8>, key: &Vec<u8>) -> String {
-        QuantumCryptography::decrypt(ciphertext, key)
+        QuantumCryptography::decrypt_stream(ciphertext, key)
     }
 
-    /// Introduces errors into a specific quantum node.
+    /// Introduces errors into a specific quantum node, sampled from the noise model.
     ///
     /// # Arguments
     /// * `node_id` - The ID of the node where errors will be introduced.
     ///
     /// # Returns
-    /// * `Option<String>` - The type of error if introduced.
-    pub fn introduce_errors(&mut self, node_id: u32) -> Option<String> {
-        if let Some(node) = self.network.get_node_mut(node_id) {
-            let error = QuantumErrorCorrection::introduce_error(node);
-            Some(format!("{:?}", error))
-        } else {
-            None
+    /// * `Option<QuantumError>` - The type of error if introduced, `None` if the node
+    ///   does not exist or the noise model produced no error.
+    pub fn introduce_errors(&mut self, node_id: u32) -> Option<QuantumError> {
+        self.network.get_node(node_id)?;
+        let error = self.noise_model.sample(self.network.rng())?;
+        QuantumErrorCorrection::apply_error(self.network.get_node_mut(node_id)?, &error);
+        self.network.degrade_links(node_id, ERROR_FIDELITY_LOSS);
+        self.metrics.errors_introduced += 1;
+        self.events.push(SimEvent::ErrorIntroduced {
+            time: self.clock,
+            node_id,
+            error: error.clone(),
+        });
+        Some(error)
+    }
+
+    /// Executes a scenario of commands in order.
+    ///
+    /// A failing command does not stop the scenario; its failure is reported in
+    /// its result and execution continues with the next command.
+    ///
+    /// # Arguments
+    /// * `commands` - The commands to execute.
+    ///
+    /// # Returns
+    /// * `Vec<SimResult>` - One result per command, in the same order.
+    pub fn run_scenario(&mut self, commands: Vec<SimCommand>) -> Vec<SimResult> {
+        commands.into_iter().map(|command| self.run_command(command)).collect()
+    }
+
+    /// Executes a single scenario command.
+    fn run_command(&mut self, command: SimCommand) -> SimResult {
+        let outcome = |success: bool| if success { SimResult::Ok } else { SimResult::Failed };
+        match command {
+            SimCommand::AddNode(node_id) => {
+                self.add_node(node_id);
+                SimResult::Ok
+            }
+            SimCommand::Entangle(node_id_1, node_id_2) => outcome(self.entangle_nodes(node_id_1, node_id_2)),
+            SimCommand::Qkd(node_id_1, node_id_2) => {
+                self.perform_qkd(node_id_1, node_id_2).map_or(SimResult::Failed, SimResult::Key)
+            }
+            SimCommand::Send { from, to, msg } => match self.transmit_with_delay(from, to, &msg) {
+                Some((packet, delay)) if self.deliver_packet(to, &packet).is_some() => SimResult::Delivered { delay },
+                _ => SimResult::Failed,
+            },
+            SimCommand::InjectError(node_id) => match self.introduce_errors(node_id) {
+                Some(error) => SimResult::Error(error),
+                None => outcome(self.network.get_node(node_id).is_some()),
+            },
+            SimCommand::Correct(node_id) => outcome(self.detect_and_correct_errors(node_id)),
+            SimCommand::Tick(dt) => {
+                self.tick(dt);
+                SimResult::Ok
+            }
         }
     }
 
@@ -108,11 +460,146 @@ impl QuantumSimulator {
     /// # Returns
     /// * `bool` - `true` if the error was detected and corrected, `false` otherwise.
     pub fn detect_and_correct_errors(&mut self, node_id: u32) -> bool {
-        if let Some(node) = self.network.get_node_mut(node_id) {
-            let expected_state = QuantumNode::new(node_id).state; // Assume ideal state
-            QuantumErrorCorrection::correct_error(node, &expected_state)
-        } else {
-            false
+        let corrected = match self.network.get_node_mut(node_id) {
+            Some(node) => {
+                let expected_state = QuantumNode::new(node_id).state; // Assume ideal state
+                QuantumErrorCorrection::restore_state(node, &expected_state)
+            }
+            None => false,
+        };
+        if corrected {
+            self.events.push(SimEvent::ErrorCorrected { time: self.clock, node_id });
+            self.metrics.errors_corrected += 1;
+        }
+        corrected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Commands that add two nodes, entangle them and give them a shared key.
+    fn keyed_pair() -> Vec<SimCommand> {
+        vec![SimCommand::AddNode(1), SimCommand::AddNode(2), SimCommand::Entangle(1, 2), SimCommand::Qkd(1, 2)]
+    }
+
+    #[test]
+    fn send_delivers_the_message_to_the_receiver() {
+        let mut simulator = QuantumSimulator::with_seed(3);
+        let mut commands = keyed_pair();
+        commands.push(SimCommand::Send { from: 1, to: 2, msg: "hi".to_string() });
+
+        let results = simulator.run_scenario(commands);
+        assert_eq!(results[4], SimResult::Delivered { delay: 0.0 });
+        assert_eq!(simulator.metrics().messages_sent, 1);
+        assert_eq!(simulator.metrics().messages_decrypted_ok, 1);
+        assert_eq!(simulator.metrics().reliability(), Some(1.0));
+    }
+
+    #[test]
+    fn send_without_a_key_fails() {
+        let mut simulator = QuantumSimulator::with_seed(3);
+        let results = simulator.run_scenario(vec![
+            SimCommand::AddNode(1),
+            SimCommand::AddNode(2),
+            SimCommand::Send { from: 1, to: 2, msg: "hi".to_string() },
+        ]);
+
+        assert_eq!(results[2], SimResult::Failed);
+        assert_eq!(simulator.metrics().messages_decrypted_ok, 0);
+    }
+
+    #[test]
+    fn metrics_count_every_step_of_a_run() {
+        let mut simulator = QuantumSimulator::with_seed(3);
+        simulator.set_noise_model(NoiseModel::new(1.0, 0.0, 0.0).unwrap());
+        let mut commands = keyed_pair();
+        commands.extend([
+            SimCommand::Send { from: 1, to: 2, msg: "first".to_string() },
+            SimCommand::Send { from: 2, to: 1, msg: "second".to_string() },
+            SimCommand::AddNode(3),
+            SimCommand::InjectError(3),
+            SimCommand::Correct(3),
+            SimCommand::Correct(3), // Nothing left to correct
+        ]);
+
+        let results = simulator.run_scenario(commands);
+        assert_eq!(results[6], SimResult::Ok);
+        assert_eq!(results[7], SimResult::Error(QuantumError::BitFlip));
+        assert_eq!(results[8], SimResult::Ok);
+        assert_eq!(results[9], SimResult::Failed);
+
+        let mut qber_histogram = [0; QBER_HISTOGRAM_BINS];
+        qber_histogram[0] = 1; // One QKD run over a perfect (fidelity 1) link
+        assert_eq!(
+            *simulator.metrics(),
+            SimMetrics {
+                messages_sent: 2,
+                messages_decrypted_ok: 2,
+                errors_introduced: 1,
+                errors_corrected: 1,
+                qber_histogram,
+            }
+        );
+    }
+
+    #[test]
+    fn qber_observations_land_in_their_bins() {
+        let mut metrics = SimMetrics::default();
+        for qber in [0.0, 0.049, 0.06, 0.26, 0.5, 0.9, -0.1] {
+            metrics.record_qber(qber);
         }
+
+        assert_eq!(metrics.qber_histogram, [3, 1, 0, 0, 0, 1, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn partition_blocks_cross_group_routing_until_healed() {
+        let mut simulator = QuantumSimulator::with_seed(3);
+        for id in 1..=4 {
+            simulator.add_node(id);
+        }
+        for (a, b) in [(1, 2), (2, 3), (3, 4)] {
+            assert!(simulator.entangle_nodes(a, b));
+        }
+        assert_eq!(simulator.find_entanglement_path(1, 4), Some(vec![1, 2, 3, 4]));
+
+        simulator.partition(&[vec![1, 2], vec![3, 4]]);
+        assert_eq!(simulator.find_entanglement_path(1, 4), None);
+        assert_eq!(simulator.find_entanglement_path(1, 2), Some(vec![1, 2]));
+        assert_eq!(simulator.find_entanglement_path(3, 4), Some(vec![3, 4]));
+        assert!(!simulator.entangle_nodes(1, 4)); // New cross-group links are refused too
+
+        simulator.heal();
+        assert_eq!(simulator.find_entanglement_path(1, 4), Some(vec![1, 2, 3, 4]));
+        assert!(simulator.entangle_nodes(1, 4));
+    }
+
+    #[test]
+    fn same_seed_simulators_produce_identical_runs() {
+        let run = |seed: u64| {
+            let mut simulator = QuantumSimulator::with_seed(seed);
+            simulator.set_noise_model(NoiseModel::new(0.3, 0.3, 0.2).unwrap());
+            simulator.set_decoherence_rate(0.5);
+            let mut commands = keyed_pair();
+            commands.extend([
+                SimCommand::AddNode(3),
+                SimCommand::Entangle(2, 3),
+                SimCommand::Qkd(2, 3),
+                SimCommand::InjectError(1),
+                SimCommand::InjectError(3),
+                SimCommand::Tick(1.0),
+                SimCommand::Entangle(1, 2),
+                SimCommand::Qkd(1, 2),
+                SimCommand::Send { from: 1, to: 2, msg: "hi".to_string() },
+            ]);
+            let results = simulator.run_scenario(commands);
+            (results, simulator.events().to_vec(), simulator.metrics().clone())
+        };
+
+        let (results, events, metrics) = run(21);
+        assert_eq!(run(21), (results.clone(), events, metrics));
+        assert_ne!(run(22).0[3], results[3]); // Another seed draws another first key
     }
 }